@@ -0,0 +1,48 @@
+//! Benchmarks parsing and solving every day against its input in `inputs/`.
+//!
+//! Run with `cargo bench`, optionally filtered by day: `cargo bench -- day7 day17`.
+
+use aoc2021::bench::{self, Config};
+use aoc2021::inputs::{self, Cache};
+use aoc2021::solution::SOLUTIONS;
+use std::path::Path;
+
+fn main() {
+    let filters: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+    let config = Config::default();
+    let cache = Cache::new(Path::new(env!("CARGO_MANIFEST_DIR")).join(inputs::DEFAULT_DIR));
+
+    let mut measurements = Vec::new();
+    for solution in SOLUTIONS {
+        let name = format!("day{}", solution.day());
+        if !filters.is_empty() && !filters.contains(&name) {
+            continue;
+        }
+        let input = match cache.load(solution.day()) {
+            Ok(input) => input,
+            Err(_) => continue,
+        };
+        match bench::bench(*solution, &input, &config) {
+            Ok(results) => {
+                for m in results.iter() {
+                    println!(
+                        "{}/{:<6}  time: [{} {} {}]",
+                        name,
+                        m.stage,
+                        bench::format_duration(m.min()),
+                        bench::format_duration(m.median()),
+                        bench::format_duration(m.max())
+                    );
+                }
+                measurements.extend(results);
+            }
+            Err(e) => println!("{}: {}", name, e),
+        }
+    }
+
+    println!();
+    print!("{}", bench::table(&measurements));
+}
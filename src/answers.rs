@@ -0,0 +1,272 @@
+//! A record of submitted answers, read from a TOML file such as:
+//!
+//! ```toml
+//! [day1.part1]
+//! correct = 1676
+//!
+//! [day1.part2]
+//! wrong = [1705]
+//! too_high = [2000, 1800]
+//! too_low = 900
+//! ```
+//!
+//! Only this subset of TOML is understood: `[dayN.partM]` tables holding `correct`, `wrong`,
+//! `too_high` and `too_low`, whose values are integers, basic strings, or single-line arrays
+//! of them.
+
+use crate::error::PuzzleError;
+use crate::parse::{lines, Span};
+use crate::solution::{Answer, Part};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::BufRead;
+
+/// The default location of the answers file, relative to the crate root.
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+/// Everything known about the answer to one part of one day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    pub correct: Option<Answer>,
+    pub wrong: Vec<Answer>,
+    pub too_high: Vec<Answer>,
+    pub too_low: Vec<Answer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect {
+        expected: Answer,
+    },
+    /// The answer was submitted before and rejected.
+    KnownWrong,
+    /// The answer is at least a value that was rejected as too high.
+    TooHigh {
+        bound: Answer,
+    },
+    /// The answer is at most a value that was rejected as too low.
+    TooLow {
+        bound: Answer,
+    },
+    /// Nothing recorded confirms or contradicts the answer.
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Verdict::Correct | Verdict::Unknown)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.pad("correct"),
+            Verdict::Incorrect { expected } if expected.to_string().contains('\n') => {
+                f.pad("incorrect")
+            }
+            Verdict::Incorrect { expected } => f.pad(&format!("incorrect, expected {}", expected)),
+            Verdict::KnownWrong => f.pad("previously rejected"),
+            Verdict::TooHigh { bound } => f.pad(&format!("too high, must be below {}", bound)),
+            Verdict::TooLow { bound } => f.pad(&format!("too low, must be above {}", bound)),
+            Verdict::Unknown => f.pad("unknown"),
+        }
+    }
+}
+
+impl Record {
+    pub fn check(&self, answer: &Answer) -> Verdict {
+        if let Some(correct) = &self.correct {
+            if answer.same_as(correct) {
+                return Verdict::Correct;
+            }
+            return Verdict::Incorrect {
+                expected: correct.clone(),
+            };
+        }
+        if self.wrong.iter().any(|wrong| answer.same_as(wrong)) {
+            return Verdict::KnownWrong;
+        }
+        if let Some(n) = answer.as_i128() {
+            let lowest_high = self
+                .too_high
+                .iter()
+                .filter(|bound| bound.as_i128().is_some_and(|bound| n >= bound))
+                .min_by_key(|bound| bound.as_i128());
+            if let Some(bound) = lowest_high {
+                return Verdict::TooHigh {
+                    bound: bound.clone(),
+                };
+            }
+            let highest_low = self
+                .too_low
+                .iter()
+                .filter(|bound| bound.as_i128().is_some_and(|bound| n <= bound))
+                .max_by_key(|bound| bound.as_i128());
+            if let Some(bound) = highest_low {
+                return Verdict::TooLow {
+                    bound: bound.clone(),
+                };
+            }
+        }
+        Verdict::Unknown
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    records: BTreeMap<(u8, Part), Record>,
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: Part) -> Option<&Record> {
+        self.records.get(&(day, part))
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(record) => record.check(answer),
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((u8, Part), &Record)> {
+        self.records.iter().map(|(key, record)| (*key, record))
+    }
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Answers, PuzzleError> {
+    let mut records = BTreeMap::new();
+    let mut current = None;
+    for line in lines(reader) {
+        let line = line?;
+        let span = line.span().trim();
+        if span.is_empty() || span.text.starts_with('#') {
+            continue;
+        }
+        if span.text.starts_with('[') {
+            let key = parse_table(span)?;
+            records.entry(key).or_insert_with(Record::default);
+            current = Some(key);
+            continue;
+        }
+
+        const EXPECTED: &str = "`key = value`";
+        let (key, value) = span.split_once("=", EXPECTED)?;
+        let record = current
+            .and_then(|key| records.get_mut(&key))
+            .ok_or_else(|| span.error("a `[dayN.partM]` table before any keys"))?;
+        let values = parse_values(value.trim())?;
+        match key.trim().text {
+            "correct" => match values.as_slice() {
+                [answer] => record.correct = Some(answer.clone()),
+                _ => return Err(value.trim().error("a single correct answer")),
+            },
+            "wrong" => record.wrong.extend(values),
+            "too_high" => record.too_high.extend(values),
+            "too_low" => record.too_low.extend(values),
+            _ => {
+                return Err(key
+                    .trim()
+                    .error("`correct`, `wrong`, `too_high` or `too_low`"))
+            }
+        }
+    }
+    Ok(Answers { records })
+}
+
+fn parse_table(span: Span) -> Result<(u8, Part), PuzzleError> {
+    const EXPECTED: &str = "a table `[dayN.partM]`";
+    let inner = span.strip_prefix("[", EXPECTED)?;
+    let (inner, rest) = inner.split_once("]", EXPECTED)?;
+    if !rest.trim().is_empty() && !rest.trim().text.starts_with('#') {
+        return Err(rest.error("end of line"));
+    }
+    let (day, part) = inner.trim().split_once(".", EXPECTED)?;
+    let day = day.strip_prefix("day", EXPECTED)?.parse("a day number")?;
+    let part = part.strip_prefix("part", EXPECTED)?;
+    let part = part
+        .parse::<u8>("part 1 or 2")
+        .and_then(|n| Part::try_from(n).map_err(|_| part.error("part 1 or 2")))?;
+    Ok((day, part))
+}
+
+/// Parses a single value or an array of values, followed by nothing but an optional comment.
+fn parse_values(span: Span) -> Result<Vec<Answer>, PuzzleError> {
+    let mut values = Vec::new();
+    let rest = match span.text.strip_prefix('[') {
+        Some(_) => {
+            let mut rest = span.split_at(1).1.trim();
+            loop {
+                if let Some(after) = rest.text.strip_prefix(']') {
+                    rest = rest.split_at(rest.text.len() - after.len()).1;
+                    break;
+                }
+                let (value, after) = parse_value(rest)?;
+                values.push(value);
+                rest = after.trim();
+                if rest.text.starts_with(',') {
+                    rest = rest.split_at(1).1.trim();
+                } else if !rest.text.starts_with(']') {
+                    return Err(rest.error("`,` or `]`"));
+                }
+            }
+            rest
+        }
+        None => {
+            let (value, rest) = parse_value(span)?;
+            values.push(value);
+            rest
+        }
+    };
+    let rest = rest.trim();
+    if !rest.is_empty() && !rest.text.starts_with('#') {
+        return Err(rest.error("end of line"));
+    }
+    Ok(values)
+}
+
+/// Parses an integer or a basic string at the start of `span`, returning the rest.
+fn parse_value(span: Span) -> Result<(Answer, Span), PuzzleError> {
+    if !span.text.starts_with('"') {
+        let end = span
+            .text
+            .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == '+' || c == '_'))
+            .unwrap_or(span.text.len());
+        let (number, rest) = span.split_at(end);
+        let n: i128 = number
+            .text
+            .replace('_', "")
+            .parse()
+            .map_err(|_| number.error("an integer or a string"))?;
+        let answer = match (u64::try_from(n), i64::try_from(n)) {
+            (Ok(n), _) => Answer::Integer(n),
+            (_, Ok(n)) => Answer::Signed(n),
+            _ => return Err(number.error("an integer that fits in 64 bits")),
+        };
+        return Ok((answer, rest));
+    }
+
+    let mut text = String::new();
+    let mut chars = span.text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Answer::Text(text), span.split_at(i + 1).1)),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, '"')) => text.push('"'),
+                Some((_, '\\')) => text.push('\\'),
+                _ => {
+                    return Err(span
+                        .split_at(i)
+                        .1
+                        .error("an escape `\\n`, `\\t`, `\\\"` or `\\\\`"))
+                }
+            },
+            c => text.push(c),
+        }
+    }
+    Err(span.error("a closing `\"`"))
+}
@@ -0,0 +1,132 @@
+use crate::error::PuzzleError;
+use crate::solution::{Part, Solution};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => f.pad("parse"),
+            Stage::Solve(Part::One) => f.pad("part 1"),
+            Stage::Solve(Part::Two) => f.pad("part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Config {
+    pub samples: usize,
+    pub max_time: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            samples: 20,
+            max_time: Duration::from_secs(3),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: Stage,
+    pub samples: Vec<Duration>,
+}
+
+impl Measurement {
+    pub fn min(&self) -> Duration {
+        self.samples.iter().copied().min().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.iter().copied().max().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        sorted.get(sorted.len() / 2).copied().unwrap_or_default()
+    }
+}
+
+fn sample<T>(
+    config: &Config,
+    mut f: impl FnMut() -> Result<T, PuzzleError>,
+) -> Result<(Vec<Duration>, T), PuzzleError> {
+    let mut samples = Vec::with_capacity(config.samples);
+    let started = Instant::now();
+    loop {
+        let start = Instant::now();
+        let out = f()?;
+        samples.push(start.elapsed());
+        if samples.len() >= config.samples.max(1) || started.elapsed() >= config.max_time {
+            return Ok((samples, out));
+        }
+    }
+}
+
+pub fn bench(
+    solution: &dyn Solution,
+    input: &[u8],
+    config: &Config,
+) -> Result<Vec<Measurement>, PuzzleError> {
+    let day = solution.day();
+    let (samples, parsed) = sample(config, || solution.parse(&mut &input[..]))?;
+    let mut measurements = vec![Measurement {
+        day,
+        stage: Stage::Parse,
+        samples,
+    }];
+    for part in Part::ALL {
+        let (samples, _) = sample(config, || solution.solve_parsed(part, &parsed))?;
+        measurements.push(Measurement {
+            day,
+            stage: Stage::Solve(part),
+            samples,
+        });
+    }
+    Ok(measurements)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut sorted: Vec<&Measurement> = measurements.iter().collect();
+    sorted.sort_by_key(|m| std::cmp::Reverse(m.median()));
+
+    let mut out = format!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>7}\n",
+        "day", "stage", "min", "median", "max", "samples"
+    );
+    for m in sorted {
+        out += &format!(
+            "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>7}\n",
+            m.day,
+            m.stage,
+            format_duration(m.min()),
+            format_duration(m.median()),
+            format_duration(m.max()),
+            m.samples.len()
+        );
+    }
+    out
+}
@@ -1,35 +1,183 @@
+use crate::error::PuzzleError;
+use crate::params::params;
+use crate::parse::{lines, Span};
+use std::collections::VecDeque;
+use std::fmt;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> usize {
-    let depths: Vec<u16> = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line| line.parse::<u16>())
-        .map(Result::unwrap)
-        .collect();
-    depths
+/// A sonar depth reading.
+pub type Depth = u64;
+
+fn parse_depth(span: Span) -> Result<Depth, PuzzleError> {
+    let digits = span.text.strip_prefix('-').unwrap_or(span.text);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(span.error("a depth"));
+    }
+    if digits.len() < span.text.len() {
+        return Err(span.error("a depth that is not negative"));
+    }
+    span.parse(&format!("a depth of at most {}", Depth::MAX))
+}
+
+fn depths<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Depth, PuzzleError>> {
+    lines(reader).map(|line| parse_depth(line?.span()))
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Depth>, PuzzleError> {
+    depths(reader).collect()
+}
+
+/// Something odd about a sonar sweep that does not stop it from being solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anomaly {
+    /// A blank line where a depth was expected.
+    Gap { line: usize },
+    /// A depth that differs from the previous one by more than the threshold.
+    Jump { line: usize, from: Depth, to: Depth },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anomaly::Gap { line } => write!(f, "line {}: missing depth", line),
+            Anomaly::Jump { line, from, to } => {
+                write!(f, "line {}: depth jumps from {} to {}", line, from, to)
+            }
+        }
+    }
+}
+
+/// Reads a sonar sweep, reporting blank lines and depths that differ from the one before by
+/// more than `threshold`. Blank lines are skipped, so the depth after a gap is compared with
+/// the one before it; other lines that are not depths are still errors.
+pub fn anomalies<R: BufRead>(reader: R, threshold: Depth) -> Result<Vec<Anomaly>, PuzzleError> {
+    let mut anomalies = Vec::new();
+    let mut previous = None;
+    for line in lines(reader) {
+        let line = line?;
+        if line.text.trim().is_empty() {
+            anomalies.push(Anomaly::Gap { line: line.number });
+            continue;
+        }
+        let depth = parse_depth(line.span())?;
+        if let Some(from) = previous {
+            if depth.abs_diff(from) > threshold {
+                anomalies.push(Anomaly::Jump {
+                    line: line.number,
+                    from,
+                    to: depth,
+                });
+            }
+        }
+        previous = Some(depth);
+    }
+    Ok(anomalies)
+}
+
+params! {
+    pub struct Params {
+        /// How many depths are summed into each window compared in part 1.
+        pub part1_window: usize = 1,
+        /// How many depths are summed into each window compared in part 2.
+        pub part2_window: usize = 3,
+    }
+}
+
+/// Fails for windows of no depths, which have no sums to compare.
+fn check_width(n: usize) -> Result<(), PuzzleError> {
+    if n == 0 {
+        return Err(PuzzleError::no_solution(
+            "windows must hold at least one depth",
+        ));
+    }
+    Ok(())
+}
+
+/// Counts how often the sum of a window of `n` depths is larger than the sum of the window one
+/// depth earlier. Neighbouring windows share all but their first and last depths, so this only
+/// compares `depths[i]` with `depths[i + n]`.
+pub fn window_increases(depths: &[Depth], n: usize) -> Result<usize, PuzzleError> {
+    check_width(n)?;
+    Ok(depths
         .iter()
-        .copied()
-        .zip(depths.iter().copied().skip(1))
-        .filter(|(p, n)| n > p)
-        .count()
-}
-
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> usize {
-    let depths: Vec<u16> = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line| line.parse::<u16>())
-        .map(Result::unwrap)
-        .collect();
-    let w1 = depths
+        .zip(depths.iter().skip(n))
+        .filter(|(prev, next)| next > prev)
+        .count())
+}
+
+/// The average of every window of `n` consecutive depths, in order.
+pub fn moving_average(depths: &[Depth], n: usize) -> Vec<f64> {
+    if n == 0 || n > depths.len() {
+        return Vec::new();
+    }
+    let mut sum: u128 = depths[..n].iter().map(|&d| d as u128).sum();
+    let mut averages = vec![sum as f64 / n as f64];
+    for (old, new) in depths.iter().zip(&depths[n..]) {
+        sum = sum + *new as u128 - *old as u128;
+        averages.push(sum as f64 / n as f64);
+    }
+    averages
+}
+
+/// The largest decrease from the sum of one window of `n` depths to the next, or `None` if the
+/// sums never decrease.
+pub fn max_drop(depths: &[Depth], n: usize) -> Result<Option<Depth>, PuzzleError> {
+    check_width(n)?;
+    Ok(depths
         .iter()
-        .copied()
-        .zip(depths.iter().copied().skip(1))
-        .zip(depths.iter().copied().skip(2))
-        .map(|((a, b), c)| a + b + c);
-    let w2 = w1.clone().skip(1);
-    w1.zip(w2).filter(|(p, n)| n > p).count()
+        .zip(depths.iter().skip(n))
+        .filter(|(prev, next)| next < prev)
+        .map(|(prev, next)| prev - next)
+        .max())
+}
+
+/// The most consecutive times the sum of a window of `n` depths increased.
+pub fn longest_increasing_run(depths: &[Depth], n: usize) -> Result<usize, PuzzleError> {
+    check_width(n)?;
+    let mut longest = 0;
+    let mut run = 0;
+    for (prev, next) in depths.iter().zip(depths.iter().skip(n)) {
+        run = if next > prev { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    Ok(longest)
+}
+
+pub fn part1(depths: &[Depth]) -> Result<usize, PuzzleError> {
+    part1_with(depths, &Params::default())
+}
+
+pub fn part1_with(depths: &[Depth], params: &Params) -> Result<usize, PuzzleError> {
+    window_increases(depths, params.part1_window)
+}
+
+pub fn part2(depths: &[Depth]) -> Result<usize, PuzzleError> {
+    part2_with(depths, &Params::default())
+}
+
+pub fn part2_with(depths: &[Depth], params: &Params) -> Result<usize, PuzzleError> {
+    window_increases(depths, params.part2_window)
+}
+
+/// Counts increases between consecutive sums of `window` depths while reading them. Two
+/// neighbouring sums share all but one depth each, so only the last `window` depths are kept.
+fn streaming_increases<R: BufRead>(reader: R, window: usize) -> Result<usize, PuzzleError> {
+    let mut recent = VecDeque::with_capacity(window + 1);
+    let mut count = 0;
+    for depth in depths(reader) {
+        let depth = depth?;
+        recent.push_back(depth);
+        if recent.len() > window && recent.pop_front().is_some_and(|old| depth > old) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<usize, PuzzleError> {
+    streaming_increases(reader, 1)
+}
+
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<usize, PuzzleError> {
+    streaming_increases(reader, 3)
 }
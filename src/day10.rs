@@ -1,135 +1,128 @@
+use crate::error::PuzzleError;
+use crate::parse::{lines, Line};
 use std::io::BufRead;
 use Bracket::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Bracket {
+pub enum Bracket {
     Round,
     Square,
     Curly,
     Angle,
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let mut score = 0;
-    for result in reader.lines() {
-        let line = result.unwrap();
-        let mut stack = Vec::new();
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Token {
+    Open(Bracket),
+    Close(Bracket),
+}
 
-        for c in line.chars() {
-            match c {
-                '(' => stack.push(Round),
-                '[' => stack.push(Square),
-                '{' => stack.push(Curly),
-                '<' => stack.push(Angle),
-                ')' => {
-                    if stack.last() == Some(&Round) {
-                        stack.pop();
-                    } else {
-                        score += 3;
-                        break;
-                    }
-                }
-                ']' => {
-                    if stack.last() == Some(&Square) {
-                        stack.pop();
-                    } else {
-                        score += 57;
-                        break;
-                    }
-                }
-                '}' => {
-                    if stack.last() == Some(&Curly) {
-                        stack.pop();
-                    } else {
-                        score += 1197;
-                        break;
-                    }
-                }
-                '>' => {
-                    if stack.last() == Some(&Angle) {
-                        stack.pop();
-                    } else {
-                        score += 25137;
-                        break;
-                    }
+fn tokens(line: &Line) -> Result<Vec<Token>, PuzzleError> {
+    line.span()
+        .chars()
+        .map(|(c, span)| match c {
+            '(' => Ok(Token::Open(Round)),
+            '[' => Ok(Token::Open(Square)),
+            '{' => Ok(Token::Open(Curly)),
+            '<' => Ok(Token::Open(Angle)),
+            ')' => Ok(Token::Close(Round)),
+            ']' => Ok(Token::Close(Square)),
+            '}' => Ok(Token::Close(Curly)),
+            '>' => Ok(Token::Close(Angle)),
+            _ => Err(span.error("one of `()[]{}<>`")),
+        })
+        .collect()
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Vec<Token>>, PuzzleError> {
+    lines(reader).map(|line| tokens(&line?)).collect()
+}
+
+/// Matches up the brackets of a line, returning the brackets still open at the end, or the
+/// first closing bracket that does not match.
+fn check(line: &[Token]) -> Result<Vec<Bracket>, Bracket> {
+    let mut stack = Vec::new();
+    for token in line.iter().copied() {
+        match token {
+            Token::Open(bracket) => stack.push(bracket),
+            Token::Close(bracket) => {
+                if stack.last() == Some(&bracket) {
+                    stack.pop();
+                } else {
+                    return Err(bracket);
                 }
-                _ => panic!(),
             }
         }
     }
-
-    score
+    Ok(stack)
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u64 {
-    let mut scores = Vec::new();
-    for result in reader.lines() {
-        let line = result.unwrap();
-        let mut stack = Vec::new();
+fn syntax_error_score(line: &[Token]) -> u32 {
+    match check(line) {
+        Ok(_) => 0,
+        Err(Round) => 3,
+        Err(Square) => 57,
+        Err(Curly) => 1197,
+        Err(Angle) => 25137,
+    }
+}
 
-        let mut invalid = false;
-        for c in line.chars() {
-            match c {
-                '(' => stack.push(Round),
-                '[' => stack.push(Square),
-                '{' => stack.push(Curly),
-                '<' => stack.push(Angle),
-                ')' => {
-                    if stack.last() == Some(&Round) {
-                        stack.pop();
-                    } else {
-                        invalid = true;
-                        break;
-                    }
-                }
-                ']' => {
-                    if stack.last() == Some(&Square) {
-                        stack.pop();
-                    } else {
-                        invalid = true;
-                        break;
-                    }
-                }
-                '}' => {
-                    if stack.last() == Some(&Curly) {
-                        stack.pop();
-                    } else {
-                        invalid = true;
-                        break;
-                    }
-                }
-                '>' => {
-                    if stack.last() == Some(&Angle) {
-                        stack.pop();
-                    } else {
-                        invalid = true;
-                        break;
-                    }
-                }
-                _ => panic!(),
-            }
-        }
+/// The score of the brackets completing an incomplete line, or `None` for a corrupted one.
+fn completion_score(line: &[Token]) -> Result<Option<u64>, PuzzleError> {
+    let Ok(stack) = check(line) else {
+        return Ok(None);
+    };
+    let mut score = 0u64;
+    for elem in stack.into_iter().rev() {
+        let points = match elem {
+            Round => 1,
+            Square => 2,
+            Curly => 3,
+            Angle => 4,
+        };
+        score = score
+            .checked_mul(5)
+            .and_then(|score| score.checked_add(points))
+            .ok_or_else(|| PuzzleError::no_solution("a completion score is too large to count"))?;
+    }
+    Ok(Some(score))
+}
 
-        if invalid {
-            continue;
-        }
+fn add_syntax_error_score(total: u64, line: &[Token]) -> Result<u64, PuzzleError> {
+    total
+        .checked_add(syntax_error_score(line).into())
+        .ok_or_else(|| PuzzleError::no_solution("the syntax error score is too large to count"))
+}
 
-        let mut score = 0;
-        for elem in stack.into_iter().rev() {
-            score *= 5;
-            score += match elem {
-                Round => 1,
-                Square => 2,
-                Curly => 3,
-                Angle => 4,
-            }
-        }
-        scores.push(score);
+fn middle_score(mut scores: Vec<u64>) -> Result<u64, PuzzleError> {
+    if scores.is_empty() {
+        return Err(PuzzleError::no_solution("every line is corrupted"));
     }
 
     scores.sort_unstable();
 
-    scores[scores.len() / 2]
+    Ok(scores[scores.len() / 2])
+}
+
+pub fn part1(lines: &[Vec<Token>]) -> Result<u64, PuzzleError> {
+    lines
+        .iter()
+        .try_fold(0, |total, line| add_syntax_error_score(total, line))
+}
+
+pub fn part2(lines: &[Vec<Token>]) -> Result<u64, PuzzleError> {
+    let mut scores = Vec::new();
+    for line in lines {
+        scores.extend(completion_score(line)?);
+    }
+    middle_score(scores)
+}
+
+/// Only part 1 streams, as finding the middle completion score means keeping every score.
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<u64, PuzzleError> {
+    let mut score = 0;
+    for line in lines(reader) {
+        score = add_syntax_error_score(score, &tokens(&line?)?)?;
+    }
+    Ok(score)
 }
@@ -1,37 +1,32 @@
+use crate::error::PuzzleError;
+use crate::grid::Grid;
+use crate::params::params;
+use crate::trace;
 use legion::world::SubWorld;
-use legion::{system, Entity, IntoQuery, Query, Resources, Schedule, World};
+use legion::{system, Entity, EntityStore, Query, Resources, Schedule, World};
 use std::io::BufRead;
 
-#[derive(Debug, Clone, Default)]
-struct Adjacencies {
-    n: Option<Entity>,
-    nw: Option<Entity>,
-    ne: Option<Entity>,
-    e: Option<Entity>,
-    se: Option<Entity>,
-    s: Option<Entity>,
-    sw: Option<Entity>,
-    w: Option<Entity>,
-}
-
-impl Adjacencies {
-    fn iter(&self) -> impl Iterator<Item = Entity> {
-        [
-            self.n, self.nw, self.ne, self.e, self.se, self.s, self.sw, self.w,
-        ]
-        .into_iter()
-        .flatten()
+params! {
+    pub struct Params {
+        /// Steps simulated before counting flashes in part 1.
+        pub part1_steps: usize = 100,
+        /// Give up on part 2 if the octopuses have not all flashed after this many steps.
+        pub part2_max_steps: usize = 100_000,
     }
 }
 
+#[derive(Debug, Clone, Default)]
+struct Adjacencies(Vec<Entity>);
+
 #[derive(Debug, Copy, Clone)]
 struct EnergyLevel(u8);
 
 #[derive(Debug, Copy, Clone)]
 struct Flashed(bool);
 
+/// The flashes so far, or `None` once there are too many to count in a `u64`.
 #[derive(Debug, Copy, Clone)]
-struct FlashCount(u32);
+struct FlashCount(Option<u64>);
 
 #[derive(Debug, Default)]
 struct EntitiesWithFlashingNeighbor(Vec<Entity>);
@@ -56,10 +51,10 @@ fn flash(
         let adjacencies: &Adjacencies = adjacencies;
         if energy_level.0 > 9 && !flashed.0 {
             flashed.0 = true;
-            flash_count.0 += 1;
-            for adjacent_entity in adjacencies.iter() {
-                entities_with_flashing_neighbor.0.push(adjacent_entity);
-            }
+            flash_count.0 = flash_count.0.and_then(|n| n.checked_add(1));
+            entities_with_flashing_neighbor
+                .0
+                .extend(adjacencies.0.iter().copied());
         }
     }
 }
@@ -97,86 +92,72 @@ fn drain(flashed: &mut Flashed, energy_level: &mut EnergyLevel) {
     }
 }
 
-fn load_entities<R: BufRead>(reader: R, world: &mut World) {
-    let entities = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| {
-            line.bytes()
-                .map(|e| e - b'0')
-                .map(|e| world.push((EnergyLevel(e), Flashed(false), Adjacencies::default())))
-                .collect::<Vec<Entity>>()
-        })
-        .collect::<Vec<Vec<Entity>>>();
-
-    let mut adjacencies_query = <&mut Adjacencies>::query();
-    for i in 0..entities.len() {
-        for j in 0..entities[0].len() {
-            for (oi, oj) in [
-                (-1, -1),
-                (-1, 0),
-                (-1, 1),
-                (0, -1),
-                (0, 1),
-                (1, -1),
-                (1, 0),
-                (1, 1),
-            ]
-            .into_iter()
-            {
-                let a = i as i32 + oi;
-                let b = j as i32 + oj;
-                if a < 0 || b < 0 {
-                    continue;
-                }
-                let a = a as usize;
-                let b = b as usize;
-                if let Some(neighbor) = entities.get(a).map(|row| row.get(b)).flatten().copied() {
-                    let adj = adjacencies_query.get_mut(world, entities[i][j]).unwrap();
-                    match (oi, oj) {
-                        (-1, -1) => adj.nw = Some(neighbor),
-                        (-1, 0) => adj.n = Some(neighbor),
-                        (-1, 1) => adj.ne = Some(neighbor),
-                        (0, -1) => adj.w = Some(neighbor),
-                        (0, 1) => adj.e = Some(neighbor),
-                        (1, -1) => adj.sw = Some(neighbor),
-                        (1, 0) => adj.s = Some(neighbor),
-                        (1, 1) => adj.se = Some(neighbor),
-                        _ => panic!(),
-                    }
-                }
-            }
-        }
-    }
+/// The octopus grid as a legion world, advanced one step at a time.
+pub struct Cavern {
+    world: World,
+    resources: Resources,
+    entities: Grid<Entity>,
+    increment_schedule: Schedule,
+    flash_schedule: Schedule,
+    cleanup_schedule: Schedule,
+    steps: usize,
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let mut world = World::default();
-    let mut resources = Resources::default();
-    resources.insert(FlashCount(0));
-    resources.insert(EntitiesWithFlashingNeighbor::default());
-
-    load_entities(reader, &mut world);
-
-    let mut increment_schedule = Schedule::builder()
-        .add_system(increment_energy_level_system())
-        .build();
-
-    let mut flash_schedule = Schedule::builder()
-        .add_system(flash_system())
-        .flush()
-        .add_system(accumulate_flashes_system())
-        .build();
+impl Cavern {
+    pub fn new(grid: &Grid<u8>) -> Self {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(FlashCount(Some(0)));
+        resources.insert(EntitiesWithFlashingNeighbor::default());
+        resources.insert(AllFlashed(false));
+
+        let entities = grid.map(|e| world.push((EnergyLevel(*e), Flashed(false))));
+        for (p, entity) in entities.iter() {
+            let neighbors = entities.neighbors8(p).map(|n| entities[n]).collect();
+            world
+                .entry(*entity)
+                .expect("entity was just pushed")
+                .add_component(Adjacencies(neighbors));
+        }
 
-    let mut cleanup_schedule = Schedule::builder().add_system(drain_system()).build();
+        let increment_schedule = Schedule::builder()
+            .add_system(increment_energy_level_system())
+            .build();
+
+        let flash_schedule = Schedule::builder()
+            .add_system(flash_system())
+            .flush()
+            .add_system(accumulate_flashes_system())
+            .build();
+
+        let cleanup_schedule = Schedule::builder()
+            .add_system(check_all_flashed_system())
+            .flush()
+            .add_system(drain_system())
+            .build();
+
+        Cavern {
+            world,
+            resources,
+            entities,
+            increment_schedule,
+            flash_schedule,
+            cleanup_schedule,
+            steps: 0,
+        }
+    }
 
-    for _ in 0..100 {
-        increment_schedule.execute(&mut world, &mut resources);
+    /// Runs one step and returns how many octopuses flashed during it.
+    pub fn step(&mut self) -> Result<u64, PuzzleError> {
+        let _step = trace::span("step");
+        let before = self.flashes()?;
+        let (world, resources) = (&mut self.world, &mut self.resources);
+        self.increment_schedule.execute(world, resources);
 
+        let cascade = trace::span("flash cascade");
         let mut previous_flashes = resources.get_mut::<FlashCount>().unwrap().0;
         loop {
-            flash_schedule.execute(&mut world, &mut resources);
+            self.flash_schedule.execute(world, resources);
 
             let new_flashes = resources.get_mut::<FlashCount>().unwrap().0;
             if previous_flashes == new_flashes {
@@ -184,62 +165,76 @@ pub fn part1<R: BufRead>(reader: R) -> u32 {
             }
             previous_flashes = new_flashes;
         }
+        drop(cascade);
+
+        resources.get_mut::<AllFlashed>().unwrap().0 = false;
+        self.cleanup_schedule.execute(world, resources);
+        self.steps += 1;
+        Ok(self.flashes()? - before)
+    }
 
-        cleanup_schedule.execute(&mut world, &mut resources);
+    /// The number of steps run so far.
+    pub fn steps(&self) -> usize {
+        self.steps
     }
 
-    let flash_count = resources.get_mut::<FlashCount>().unwrap().0;
-    flash_count
+    /// The number of flashes over all steps so far.
+    pub fn flashes(&self) -> Result<u64, PuzzleError> {
+        self.resources
+            .get::<FlashCount>()
+            .unwrap()
+            .0
+            .ok_or_else(|| PuzzleError::no_solution("the flashes do not fit in 64 bits"))
+    }
+
+    /// Whether every octopus flashed during the last step.
+    pub fn all_flashed(&self) -> bool {
+        self.resources.get::<AllFlashed>().unwrap().0
+    }
+
+    pub fn energy_levels(&self) -> Grid<u8> {
+        self.entities.map(|entity| {
+            self.world
+                .entry_ref(*entity)
+                .expect("octopuses are never removed")
+                .get_component::<EnergyLevel>()
+                .expect("every octopus has an energy level")
+                .0
+        })
+    }
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u32 {
-    let mut world = World::default();
-    let mut resources = Resources::default();
-    resources.insert(FlashCount(0));
-    resources.insert(EntitiesWithFlashingNeighbor::default());
-    resources.insert(AllFlashed(false));
-
-    load_entities(reader, &mut world);
-
-    let mut increment_schedule = Schedule::builder()
-        .add_system(increment_energy_level_system())
-        .build();
-
-    let mut flash_schedule = Schedule::builder()
-        .add_system(flash_system())
-        .flush()
-        .add_system(accumulate_flashes_system())
-        .build();
-
-    let mut cleanup_schedule = Schedule::builder()
-        .add_system(check_all_flashed_system())
-        .flush()
-        .add_system(drain_system())
-        .build();
-
-    let mut current_step = 0;
-    loop {
-        current_step += 1;
-        increment_schedule.execute(&mut world, &mut resources);
+pub fn parse<R: BufRead>(reader: R) -> Result<Grid<u8>, PuzzleError> {
+    Grid::parse_digits(reader)
+}
 
-        let mut previous_flashes = resources.get_mut::<FlashCount>().unwrap().0;
-        loop {
-            flash_schedule.execute(&mut world, &mut resources);
+pub fn part1(grid: &Grid<u8>) -> Result<u64, PuzzleError> {
+    part1_with(grid, &Params::default())
+}
 
-            let new_flashes = resources.get_mut::<FlashCount>().unwrap().0;
-            if previous_flashes == new_flashes {
-                break;
-            }
-            previous_flashes = new_flashes;
-        }
+pub fn part1_with(grid: &Grid<u8>, params: &Params) -> Result<u64, PuzzleError> {
+    let mut cavern = Cavern::new(grid);
+    for _ in 0..params.part1_steps {
+        cavern.step()?;
+    }
+    cavern.flashes()
+}
 
-        cleanup_schedule.execute(&mut world, &mut resources);
+pub fn part2(grid: &Grid<u8>) -> Result<usize, PuzzleError> {
+    part2_with(grid, &Params::default())
+}
 
-        if resources.get::<AllFlashed>().unwrap().0 {
-            break;
+pub fn part2_with(grid: &Grid<u8>, params: &Params) -> Result<usize, PuzzleError> {
+    let mut cavern = Cavern::new(grid);
+    while cavern.steps() < params.part2_max_steps {
+        cavern.step()?;
+        if cavern.all_flashed() {
+            return Ok(cavern.steps());
         }
     }
 
-    current_step
+    Err(PuzzleError::no_solution(format!(
+        "the octopuses did not all flash within {} steps",
+        params.part2_max_steps
+    )))
 }
@@ -1,16 +1,39 @@
+use crate::error::PuzzleError;
+use crate::parse::lines;
 use std::collections::{HashMap, HashSet};
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
+pub fn parse<R: BufRead>(reader: R) -> Result<HashMap<String, Vec<String>>, PuzzleError> {
     let mut edges: HashMap<String, Vec<String>> = HashMap::new();
-    for res in reader.lines() {
-        let line = res.unwrap();
-        let (a, b) = line.split_once('-').unwrap();
-        edges.entry(a.to_string()).or_default().push(b.to_string());
-        edges.entry(b.to_string()).or_default().push(a.to_string());
+    for line in lines(reader) {
+        let line = line?;
+        let (a, b) = line.span().split_once("-", "an edge `a-b`")?;
+        for cave in [a, b] {
+            if cave.is_empty() || !cave.text.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(cave.error("a cave name"));
+            }
+        }
+        // Paths could bounce between two big caves forever.
+        if [a, b].iter().all(|cave| is_big(cave.text)) {
+            return Err(line.span().error("an edge with at least one small cave"));
+        }
+        edges
+            .entry(a.text.to_string())
+            .or_default()
+            .push(b.text.to_string());
+        edges
+            .entry(b.text.to_string())
+            .or_default()
+            .push(a.text.to_string());
     }
+    Ok(edges)
+}
 
+fn is_big(cave: &str) -> bool {
+    cave.chars().all(|c| c.is_ascii_uppercase())
+}
+
+pub fn part1(edges: &HashMap<String, Vec<String>>) -> Result<u32, PuzzleError> {
     let mut path = Vec::new();
     path.push((String::from("start"), 0));
 
@@ -21,7 +44,7 @@ pub fn part1<R: BufRead>(reader: R) -> u32 {
             Some(v) => v,
         };
 
-        let next = match edges.get(&last).map(|l| l.get(last_idx)).flatten() {
+        let next = match edges.get(&last).and_then(|l| l.get(last_idx)) {
             None => continue,
             Some(next) => next,
         };
@@ -33,26 +56,17 @@ pub fn part1<R: BufRead>(reader: R) -> u32 {
             continue;
         }
 
-        if !next.chars().all(|c| c.is_ascii_uppercase()) && path.iter().any(|(v, _)| v == next) {
+        if !is_big(next) && path.iter().any(|(v, _)| v == next) {
             continue;
         }
 
         path.push((next.clone(), 0));
     }
 
-    count
+    Ok(count)
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u32 {
-    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
-    for res in reader.lines() {
-        let line = res.unwrap();
-        let (a, b) = line.split_once('-').unwrap();
-        edges.entry(a.to_string()).or_default().push(b.to_string());
-        edges.entry(b.to_string()).or_default().push(a.to_string());
-    }
-
+pub fn part2(edges: &HashMap<String, Vec<String>>) -> Result<u32, PuzzleError> {
     let mut path = Vec::new();
     path.push((String::from("start"), 0));
 
@@ -63,7 +77,7 @@ pub fn part2<R: BufRead>(reader: R) -> u32 {
             Some(v) => v,
         };
 
-        let next = match edges.get(&last).map(|l| l.get(last_idx)).flatten() {
+        let next = match edges.get(&last).and_then(|l| l.get(last_idx)) {
             None => continue,
             Some(next) => next,
         };
@@ -79,12 +93,12 @@ pub fn part2<R: BufRead>(reader: R) -> u32 {
             continue;
         }
 
-        let next_is_uppercase = || next.chars().all(|c| c.is_ascii_uppercase());
+        let next_is_uppercase = || is_big(next);
         let next_in_path = || path.iter().any(|(v, _)| v == next);
         let path_has_dupe = || {
             let mut existing = HashSet::new();
             for (seg, _) in path.iter() {
-                if seg.chars().all(|c| c.is_ascii_uppercase()) {
+                if is_big(seg) {
                     continue;
                 }
                 if existing.contains(seg) {
@@ -100,5 +114,5 @@ pub fn part2<R: BufRead>(reader: R) -> u32 {
         }
     }
 
-    count
+    Ok(count)
 }
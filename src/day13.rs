@@ -1,124 +1,119 @@
-use std::collections::HashSet;
+use crate::error::PuzzleError;
+use crate::grid::{Point, SparseGrid};
+use crate::parse::lines;
+use std::fmt;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> usize {
-    let mut scratch = Vec::<(u16, u16)>::new();
-    let mut grid = HashSet::<(u16, u16)>::new();
-    let mut phase_two = false;
-    for res in reader.lines() {
-        let line = res.unwrap();
-        if line.is_empty() {
-            phase_two = true;
-            continue;
-        }
-        if !phase_two {
-            let (x_text, y_text) = line.split_once(',').unwrap();
-            let x = x_text.parse().unwrap();
-            let y = y_text.parse().unwrap();
-            grid.insert((x, y));
-        } else {
-            let eq = line.split(' ').skip(2).next().unwrap();
-            let (axis, val_text) = eq.split_once('=').unwrap();
-            let val: u16 = val_text.parse().unwrap();
-            match axis {
-                "x" => {
-                    grid.iter()
-                        .copied()
-                        .filter(|(x, _)| *x > val)
-                        .for_each(|coord| scratch.push(coord));
-                    scratch.iter().for_each(|coord| {
-                        grid.remove(coord);
-                    });
-                    for (x, y) in scratch.drain(..) {
-                        grid.insert((val - (x - val), y));
-                    }
-                }
-                "y" => {
-                    grid.iter()
-                        .copied()
-                        .filter(|(_, y)| *y > val)
-                        .for_each(|coord| scratch.push(coord));
-                    scratch.iter().for_each(|coord| {
-                        grid.remove(coord);
-                    });
-                    for (x, y) in scratch.drain(..) {
-                        grid.insert((x, val - (y - val)));
-                    }
-                }
-                _ => panic!(),
-            }
-            return grid.len();
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Fold {
+    X(usize),
+    Y(usize),
+}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fold::X(val) => write!(f, "fold along x={}", val),
+            Fold::Y(val) => write!(f, "fold along y={}", val),
         }
     }
+}
 
-    unimplemented!()
+#[derive(Debug, Clone)]
+pub struct Manual {
+    pub dots: SparseGrid<()>,
+    pub folds: Vec<Fold>,
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> String {
-    let mut scratch = Vec::<(u16, u16)>::new();
-    let mut grid = HashSet::<(u16, u16)>::new();
+pub fn parse<R: BufRead>(reader: R) -> Result<Manual, PuzzleError> {
+    let mut grid = SparseGrid::new();
+    let mut folds = Vec::new();
     let mut phase_two = false;
-    for res in reader.lines() {
-        let line = res.unwrap();
-        if line.is_empty() {
+    for line in lines(reader) {
+        let line = line?;
+        if line.text.is_empty() {
             phase_two = true;
             continue;
         }
         if !phase_two {
-            let (x_text, y_text) = line.split_once(',').unwrap();
-            let x = x_text.parse().unwrap();
-            let y = y_text.parse().unwrap();
-            grid.insert((x, y));
+            const EXPECTED: &str = "a dot `x,y`";
+            let (x_text, y_text) = line.span().split_once(",", EXPECTED)?;
+            let x = x_text.parse(EXPECTED)?;
+            let y = y_text.parse(EXPECTED)?;
+            grid.insert((x, y), ());
         } else {
-            let eq = line.split(' ').skip(2).next().unwrap();
-            let (axis, val_text) = eq.split_once('=').unwrap();
-            let val: u16 = val_text.parse().unwrap();
-            match axis {
-                "x" => {
-                    grid.iter()
-                        .copied()
-                        .filter(|(x, _)| *x > val)
-                        .for_each(|coord| scratch.push(coord));
-                    scratch.iter().for_each(|coord| {
-                        grid.remove(coord);
-                    });
-                    for (x, y) in scratch.drain(..) {
-                        grid.insert((val - (x - val), y));
-                    }
-                }
-                "y" => {
-                    grid.iter()
-                        .copied()
-                        .filter(|(_, y)| *y > val)
-                        .for_each(|coord| scratch.push(coord));
-                    scratch.iter().for_each(|coord| {
-                        grid.remove(coord);
-                    });
-                    for (x, y) in scratch.drain(..) {
-                        grid.insert((x, val - (y - val)));
-                    }
-                }
-                _ => panic!(),
-            }
+            const EXPECTED: &str = "a fold `fold along x=N` or `fold along y=N`";
+            let eq = line.span().strip_prefix("fold along ", EXPECTED)?;
+            let (axis, val_text) = eq.split_once("=", EXPECTED)?;
+            let val = val_text.parse("a fold position")?;
+            folds.push(match axis.text {
+                "x" => Fold::X(val),
+                "y" => Fold::Y(val),
+                _ => return Err(axis.error("`x` or `y`")),
+            });
         }
     }
+    Ok(Manual { dots: grid, folds })
+}
 
-    let max_x = grid.iter().map(|(x, _)| *x).max().unwrap();
-    let max_y = grid.iter().map(|(_, y)| *y).max().unwrap();
+fn fold(
+    grid: &mut SparseGrid<()>,
+    scratch: &mut Vec<Point>,
+    fold: Fold,
+) -> Result<(), PuzzleError> {
+    let overflow = || {
+        PuzzleError::no_solution(format!(
+            "`{}` would move dots past the edge of the paper",
+            fold
+        ))
+    };
+    let reflect = |(x, y): Point| match fold {
+        Fold::X(val) if x > val => Some(val.checked_sub(x - val).map(|x| (x, y))),
+        Fold::Y(val) if y > val => Some(val.checked_sub(y - val).map(|y| (x, y))),
+        _ => None,
+    };
+    scratch.extend(grid.points().filter(|p| reflect(*p).is_some()));
+    for p in scratch.drain(..) {
+        grid.remove(p);
+        let folded = reflect(p).flatten().ok_or_else(overflow)?;
+        grid.insert(folded, ());
+    }
+    Ok(())
+}
 
-    let mut out = String::new();
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            if grid.contains(&(x, y)) {
-                out.push('█');
-            } else {
-                out.push(' ');
-            }
+impl Manual {
+    /// The dots left after making the first `n` folds.
+    pub fn folded(&self, n: usize) -> Result<SparseGrid<()>, PuzzleError> {
+        let mut grid = self.dots.clone();
+        let mut scratch = Vec::new();
+        for f in self.folds.iter().take(n).copied() {
+            fold(&mut grid, &mut scratch, f)?;
         }
-        out.push('\n');
+        Ok(grid)
+    }
+}
+
+/// The most cells a drawing of the paper may have, which is far more than a readable code needs.
+pub const MAX_RENDERED_CELLS: usize = 1 << 20;
+
+pub fn render(dots: &SparseGrid<()>) -> Result<String, PuzzleError> {
+    let (width, height) = dots.bounds()?;
+    if width.saturating_mul(height) > MAX_RENDERED_CELLS {
+        return Err(PuzzleError::no_solution(format!(
+            "the dots span {} by {} cells, too many to draw",
+            width, height
+        )));
     }
+    dots.render(|dot| if dot.is_some() { '█' } else { ' ' })
+}
+
+pub fn part1(manual: &Manual) -> Result<usize, PuzzleError> {
+    if manual.folds.is_empty() {
+        return Err(PuzzleError::no_solution("there are no fold instructions"));
+    }
+    Ok(manual.folded(1)?.len())
+}
 
-    out
+pub fn part2(manual: &Manual) -> Result<String, PuzzleError> {
+    render(&manual.folded(manual.folds.len())?)
 }
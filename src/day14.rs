@@ -1,26 +1,65 @@
+use crate::error::PuzzleError;
+use crate::params::params;
+use crate::parse::lines;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u16 {
-    let mut lines = reader.lines();
-    let mut template = lines.next().unwrap().unwrap().into_bytes();
-    lines.next().unwrap().unwrap();
+params! {
+    pub struct Params {
+        /// Insertion steps for part 1, which expands the polymer string directly.
+        pub part1_steps: usize = 10,
+        /// Insertion steps for part 2, which only counts pairs.
+        pub part2_steps: usize = 40,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    pub template: Vec<u8>,
+    pub replacements: HashMap<(u8, u8), u8>,
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Polymer, PuzzleError> {
+    let mut lines = lines(reader);
+    let template = lines.expect("a polymer template")?;
+    if template.text.is_empty() {
+        return Err(template.span().error("a polymer template"));
+    }
+    let separator = lines.expect("an empty line")?;
+    if !separator.text.is_empty() {
+        return Err(separator.span().error("an empty line"));
+    }
 
     let mut replacements = HashMap::<(u8, u8), u8>::new();
-    for res in lines {
-        let line = res.unwrap();
-        let (from, to) = line.split_once(" -> ").unwrap();
-        match (from.as_bytes(), to.as_bytes()) {
+    for line in lines {
+        let line = line?;
+        const EXPECTED: &str = "a pair insertion rule `AB -> C`";
+        let (from, to) = line.span().split_once(" -> ", EXPECTED)?;
+        match (from.text.as_bytes(), to.text.as_bytes()) {
             ([a, b], [r]) => {
                 replacements.insert((*a, *b), *r);
             }
-            _ => panic!(),
+            _ => return Err(line.span().error(EXPECTED)),
         }
     }
 
+    Ok(Polymer {
+        template: template.text.into_bytes(),
+        replacements,
+    })
+}
+
+pub fn part1(polymer: &Polymer) -> Result<usize, PuzzleError> {
+    part1_with(polymer, &Params::default())
+}
+
+pub fn part1_with(polymer: &Polymer, params: &Params) -> Result<usize, PuzzleError> {
+    let replacements = &polymer.replacements;
+    let mut template = polymer.template.clone();
+
     let mut scratch = Vec::new();
-    for _ in 0..10 {
+    for _ in 0..params.part1_steps {
         scratch.clear();
         for (a, b) in template
             .iter()
@@ -36,31 +75,32 @@ pub fn part1<R: BufRead>(reader: R) -> u16 {
         std::mem::swap(&mut template, &mut scratch);
     }
 
-    let mut frequencies = HashMap::<u8, u16>::new();
+    let mut frequencies = HashMap::<u8, usize>::new();
     for c in template {
         *frequencies.entry(c).or_default() += 1;
     }
 
-    frequencies.values().max().copied().unwrap() - frequencies.values().min().copied().unwrap()
+    Ok(frequencies.values().max().copied().unwrap() - frequencies.values().min().copied().unwrap())
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u64 {
-    let mut lines = reader.lines();
-    let template = lines.next().unwrap().unwrap().into_bytes();
-    lines.next().unwrap().unwrap();
+/// Adds `n` to the count of `key`, failing if the count no longer fits in a `u64`.
+fn add<K: Eq + Hash>(counts: &mut HashMap<K, u64>, key: K, n: u64) -> Result<(), PuzzleError> {
+    let count = counts.entry(key).or_default();
+    *count = count
+        .checked_add(n)
+        .ok_or_else(|| PuzzleError::no_solution("the polymer grows too long to count"))?;
+    Ok(())
+}
 
-    let mut replacements = HashMap::<(u8, u8), u8>::new();
-    for res in lines {
-        let line = res.unwrap();
-        let (from, to) = line.split_once(" -> ").unwrap();
-        match (from.as_bytes(), to.as_bytes()) {
-            ([a, b], [r]) => {
-                replacements.insert((*a, *b), *r);
-            }
-            _ => panic!(),
-        }
-    }
+pub fn part2(polymer: &Polymer) -> Result<u64, PuzzleError> {
+    part2_with(polymer, &Params::default())
+}
+
+pub fn part2_with(polymer: &Polymer, params: &Params) -> Result<u64, PuzzleError> {
+    let Polymer {
+        template,
+        replacements,
+    } = polymer;
 
     let mut pair_frequencies = HashMap::<(u8, u8), u64>::new();
     for (a, b) in template
@@ -72,14 +112,14 @@ pub fn part2<R: BufRead>(reader: R) -> u64 {
     }
 
     let mut next_pair_frequencies = HashMap::<(u8, u8), u64>::new();
-    for _ in 0..40 {
+    for _ in 0..params.part2_steps {
         next_pair_frequencies.clear();
         for ((a, b), c) in pair_frequencies.iter() {
             if let Some(r) = replacements.get(&(*a, *b)).copied() {
-                *next_pair_frequencies.entry((*a, r)).or_default() += *c;
-                *next_pair_frequencies.entry((r, *b)).or_default() += *c;
+                add(&mut next_pair_frequencies, (*a, r), *c)?;
+                add(&mut next_pair_frequencies, (r, *b), *c)?;
             } else {
-                *next_pair_frequencies.entry((*a, *b)).or_default() += *c;
+                add(&mut next_pair_frequencies, (*a, *b), *c)?;
             }
         }
         std::mem::swap(&mut next_pair_frequencies, &mut pair_frequencies);
@@ -87,10 +127,10 @@ pub fn part2<R: BufRead>(reader: R) -> u64 {
 
     let mut frequencies = HashMap::<u8, u64>::new();
     for (i, c) in pair_frequencies.iter().map(|((a, _), c)| (*a, *c)) {
-        *frequencies.entry(i).or_default() += c;
+        add(&mut frequencies, i, c)?;
     }
 
-    *frequencies.entry(*template.last().unwrap()).or_default() += 1;
+    add(&mut frequencies, *template.last().unwrap(), 1)?;
 
-    frequencies.values().max().copied().unwrap() - frequencies.values().min().copied().unwrap()
+    Ok(frequencies.values().max().copied().unwrap() - frequencies.values().min().copied().unwrap())
 }
@@ -1,195 +1,130 @@
+use crate::error::PuzzleError;
+use crate::grid::{Grid, Point};
+use crate::params::params;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::BinaryHeap;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u16 {
-    let grid: Vec<Vec<u8>> = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| line.bytes().map(|b| b - b'0').collect())
-        .collect();
-
-    let goal = ((grid.len() - 1) as i8, (grid[0].len() - 1) as i8);
-
-    #[derive(Debug, Clone)]
-    struct Path {
-        total_risk: u16,
-        x: i8,
-        y: i8,
+pub fn parse<R: BufRead>(reader: R) -> Result<Grid<u8>, PuzzleError> {
+    Grid::parse_digits_in(reader, 1..=9)
+}
+
+params! {
+    pub struct Params {
+        /// How many times the cave is tiled in each direction for part 1.
+        pub part1_tiles: usize = 1,
+        /// How many times the cave is tiled in each direction for part 2.
+        pub part2_tiles: usize = 5,
     }
+}
 
-    impl PartialEq for Path {
-        fn eq(&self, other: &Self) -> bool {
-            self.total_risk == other.total_risk
-        }
+#[derive(Debug, Clone)]
+struct Path {
+    total_risk: u32,
+    loc: Point,
+}
+
+impl PartialEq for Path {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_risk == other.total_risk
     }
+}
 
-    impl Eq for Path {}
+impl Eq for Path {}
 
-    impl PartialOrd for Path {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            other.total_risk.partial_cmp(&self.total_risk)
-        }
+impl PartialOrd for Path {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    impl Ord for Path {
-        fn cmp(&self, other: &Self) -> Ordering {
-            other.total_risk.cmp(&self.total_risk)
-        }
+impl Ord for Path {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.total_risk.cmp(&self.total_risk)
     }
+}
+
+fn lowest_risk(grid: &Grid<u8>) -> Result<u32, PuzzleError> {
+    let goal = (grid.width() - 1, grid.height() - 1);
 
     let mut options = BinaryHeap::new();
     options.push(Path {
-        total_risk: grid[0][1] as u16,
-        x: 0,
-        y: 1,
+        total_risk: 0,
+        loc: (0, 0),
     });
-    options.push(Path {
-        total_risk: grid[1][0] as u16,
-        x: 1,
-        y: 0,
-    });
-    let mut already_considered = HashSet::new();
-    already_considered.insert((0, 0));
-    already_considered.insert((1, 0));
-    already_considered.insert((0, 1));
-
-    loop {
-        let path = options.pop().unwrap();
-        let loc = (path.x, path.y);
+    let mut already_considered = grid.map(|_| false);
+    already_considered[(0, 0)] = true;
 
-        if loc == goal {
-            return path.total_risk;
+    while let Some(path) = options.pop() {
+        if path.loc == goal {
+            return Ok(path.total_risk);
         }
 
-        for (xo, yo) in [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter() {
-            let nx = path.x + xo;
-            let ny = path.y + yo;
-            if nx < 0
-                || ny < 0
-                || nx > goal.0
-                || ny > goal.1
-                || already_considered.contains(&(nx, ny))
-            {
+        for next in grid.neighbors4(path.loc) {
+            if already_considered[next] {
                 continue;
             }
-            already_considered.insert((nx, ny));
+            already_considered[next] = true;
             options.push(Path {
-                total_risk: path.total_risk + grid[nx as usize][ny as usize] as u16,
-                x: nx,
-                y: ny,
+                total_risk: path.total_risk + grid[next] as u32,
+                loc: next,
             });
         }
     }
+    Err(PuzzleError::no_solution(
+        "the bottom right corner is unreachable",
+    ))
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u16 {
-    let mut grid: Vec<Vec<u8>> = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| line.bytes().map(|b| b - b'0').collect())
-        .collect();
-
-    let orig_x = grid.len();
-    let orig_y = grid[0].len();
-
-    for i in 0..4 {
-        for j in 0..orig_x {
-            grid.push(
-                grid[orig_x * i + j]
-                    .iter()
-                    .copied()
-                    .map(|cell| cell + 1)
-                    .map(|cell| if cell > 9 { 1 } else { cell })
-                    .collect(),
-            )
-        }
-    }
-
-    let mut scratch = Vec::new();
-    for i in 0..4 {
-        for row in grid.iter_mut() {
-            for cell in row[(orig_y * i)..(orig_y * (i + 1))].iter().copied() {
-                scratch.push(if cell + 1 > 9 { 1 } else { cell + 1 });
-            }
-            for cell in scratch.drain(..) {
-                row.push(cell);
-            }
-        }
-    }
-
-    let goal = ((grid.len() - 1) as i16, (grid[0].len() - 1) as i16);
+/// The most cells a tiled cave may have before tiling is refused.
+pub const MAX_TILED_CELLS: usize = 1 << 24;
 
-    #[derive(Debug, Clone)]
-    struct Path {
-        total_risk: u16,
-        x: i16,
-        y: i16,
+/// Repeats the cave `tiles` times in each direction, raising the risk by one per tile
+/// away from the original and wrapping from 9 back to 1.
+pub(crate) fn tile(grid: &Grid<u8>, tiles: usize) -> Result<Grid<u8>, PuzzleError> {
+    if tiles == 0 {
+        return Err(PuzzleError::no_solution("the cave is tiled zero times"));
     }
-
-    impl PartialEq for Path {
-        fn eq(&self, other: &Self) -> bool {
-            self.total_risk == other.total_risk
+    let (width, height) = (grid.width(), grid.height());
+    let size = width.checked_mul(tiles).zip(height.checked_mul(tiles));
+    let (tiled_width, tiled_height) = match size {
+        Some((w, h))
+            if w.checked_mul(h)
+                .is_some_and(|cells| cells <= MAX_TILED_CELLS) =>
+        {
+            (w, h)
         }
-    }
-
-    impl Eq for Path {}
-
-    impl PartialOrd for Path {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            other.total_risk.partial_cmp(&self.total_risk)
+        _ => {
+            return Err(PuzzleError::no_solution(format!(
+                "tiling the cave {} times makes more than {} cells",
+                tiles, MAX_TILED_CELLS
+            )))
         }
-    }
+    };
+    Grid::from_fn(tiled_width, tiled_height, |(x, y)| {
+        let bump = (x / width + y / height) % 9;
+        // Risks are 1 to 9, as parsing checks.
+        ((grid[(x % width, y % height)] as usize - 1 + bump) % 9 + 1) as u8
+    })
+}
 
-    impl Ord for Path {
-        fn cmp(&self, other: &Self) -> Ordering {
-            other.total_risk.cmp(&self.total_risk)
-        }
-    }
+/// The lowest total risk of a path through the cave tiled `tiles` times in each direction.
+pub(crate) fn lowest_tiled_risk(grid: &Grid<u8>, tiles: usize) -> Result<u32, PuzzleError> {
+    lowest_risk(&tile(grid, tiles)?)
+}
 
-    let mut options = BinaryHeap::new();
-    options.push(Path {
-        total_risk: grid[0][1] as u16,
-        x: 0,
-        y: 1,
-    });
-    options.push(Path {
-        total_risk: grid[1][0] as u16,
-        x: 1,
-        y: 0,
-    });
-    let mut already_considered = HashSet::new();
-    already_considered.insert((0, 0));
-    already_considered.insert((1, 0));
-    already_considered.insert((0, 1));
+pub fn part1(grid: &Grid<u8>) -> Result<u32, PuzzleError> {
+    part1_with(grid, &Params::default())
+}
 
-    loop {
-        let path = options.pop().unwrap();
-        let loc = (path.x, path.y);
+pub fn part1_with(grid: &Grid<u8>, params: &Params) -> Result<u32, PuzzleError> {
+    lowest_tiled_risk(grid, params.part1_tiles)
+}
 
-        if loc == goal {
-            return path.total_risk;
-        }
+pub fn part2(grid: &Grid<u8>) -> Result<u32, PuzzleError> {
+    part2_with(grid, &Params::default())
+}
 
-        for (xo, yo) in [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter() {
-            let nx = path.x + xo;
-            let ny = path.y + yo;
-            if nx < 0
-                || ny < 0
-                || nx > goal.0
-                || ny > goal.1
-                || already_considered.contains(&(nx, ny))
-            {
-                continue;
-            }
-            already_considered.insert((nx, ny));
-            options.push(Path {
-                total_risk: path.total_risk + grid[nx as usize][ny as usize] as u16,
-                x: nx,
-                y: ny,
-            });
-        }
-    }
+pub fn part2_with(grid: &Grid<u8>, params: &Params) -> Result<u32, PuzzleError> {
+    lowest_tiled_risk(grid, params.part2_tiles)
 }
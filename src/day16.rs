@@ -1,3 +1,6 @@
+use crate::error::PuzzleError;
+use crate::parse::{lines, Span};
+use std::fmt::Write;
 use std::io::BufRead;
 
 struct Biterator<'a> {
@@ -90,50 +93,112 @@ impl<'a> Biterator<'a> {
     }
 }
 
-enum PacketData {
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PacketData {
     Literal(u64),
     SubPackets(Vec<Packet>),
 }
 
-struct Packet {
-    version: u8,
-    type_id: u8,
-    data: PacketData,
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Packet {
+    pub version: u8,
+    pub type_id: u8,
+    pub data: PacketData,
+}
+
+/// How deeply packets may nest, keeping the recursive parsing and evaluation within the stack.
+pub const MAX_DEPTH: usize = 256;
+
+/// Why a packet could not be loaded.
+enum LoadError {
+    /// The transmission ended partway through a packet.
+    Truncated,
+    /// The packet starting at this bit is nested more than [`MAX_DEPTH`] deep.
+    TooDeep(usize),
 }
 
 impl Packet {
-    fn load(biterator: &mut Biterator) -> Option<Self> {
-        let version = biterator.next_u3()?;
-        let type_id = biterator.next_u3()?;
+    fn load(biterator: &mut Biterator, nesting: usize) -> Result<Self, LoadError> {
+        if nesting > MAX_DEPTH {
+            return Err(LoadError::TooDeep(biterator.depth()));
+        }
+        let truncated = || LoadError::Truncated;
+        let version = biterator.next_u3().ok_or_else(truncated)?;
+        let type_id = biterator.next_u3().ok_or_else(truncated)?;
         let data = match type_id {
-            4 => PacketData::Literal(biterator.next_var_width_int()?),
+            4 => PacketData::Literal(biterator.next_var_width_int().ok_or_else(truncated)?),
             _ => {
-                if biterator.next()? {
-                    let num_sub_packets = biterator.next_u11()?;
+                if biterator.next().ok_or_else(truncated)? {
+                    let num_sub_packets = biterator.next_u11().ok_or_else(truncated)?;
                     PacketData::SubPackets(
                         (0..num_sub_packets)
-                            .map(|_| Packet::load(biterator))
-                            .collect::<Option<Vec<Packet>>>()?,
+                            .map(|_| Packet::load(biterator, nesting + 1))
+                            .collect::<Result<Vec<Packet>, _>>()?,
                     )
                 } else {
-                    let sub_packet_length = biterator.next_u15()? as usize;
+                    let sub_packet_length = biterator.next_u15().ok_or_else(truncated)? as usize;
                     let depth = biterator.depth();
                     let mut sub_packets = Vec::new();
                     while biterator.depth() < depth + sub_packet_length {
-                        sub_packets.push(Packet::load(biterator)?);
+                        sub_packets.push(Packet::load(biterator, nesting + 1)?);
                     }
                     PacketData::SubPackets(sub_packets)
                 }
             }
         };
-        Some(Packet {
+        Ok(Packet {
             version,
             type_id,
             data,
         })
     }
 
-    fn version_sum(&self) -> u64 {
+    /// The name of the operation given by the packet's type ID.
+    pub fn operation(&self) -> &'static str {
+        match self.type_id {
+            0 => "sum",
+            1 => "product",
+            2 => "minimum",
+            3 => "maximum",
+            4 => "literal",
+            5 => "greater than",
+            6 => "less than",
+            7 => "equal to",
+            _ => "unknown",
+        }
+    }
+
+    /// Renders the packet and its sub-packets one per line, indented by depth, with each
+    /// packet's version and value.
+    pub fn tree(&self) -> String {
+        fn write(packet: &Packet, depth: usize, out: &mut String) {
+            let value = match packet.eval() {
+                Ok(value) => value.to_string(),
+                Err(_) => "invalid".to_string(),
+            };
+            writeln!(
+                out,
+                "{:indent$}{} v{} = {}",
+                "",
+                packet.operation(),
+                packet.version,
+                value,
+                indent = 2 * depth
+            )
+            .unwrap();
+            if let PacketData::SubPackets(sub_packets) = &packet.data {
+                for sub_packet in sub_packets {
+                    write(sub_packet, depth + 1, out);
+                }
+            }
+        }
+
+        let mut out = String::new();
+        write(self, 0, &mut out);
+        out
+    }
+
+    pub fn version_sum(&self) -> u64 {
         self.version as u64
             + match &self.data {
                 PacketData::Literal(_) => 0,
@@ -144,86 +209,69 @@ impl Packet {
             }
     }
 
-    fn eval(&self) -> u64 {
+    pub fn eval(&self) -> Result<u64, PuzzleError> {
+        let invalid = || PuzzleError::no_solution("an operator packet has invalid operands");
+        let overflow = || PuzzleError::no_solution("a packet's value does not fit in 64 bits");
         match &self.data {
-            PacketData::Literal(val) => *val,
-            PacketData::SubPackets(sub_packets) => match self.type_id {
-                0 => sub_packets.iter().map(|sub_packet| sub_packet.eval()).sum(),
-                1 => sub_packets
-                    .iter()
-                    .map(|sub_packet| sub_packet.eval())
-                    .product(),
-                2 => sub_packets
+            PacketData::Literal(val) => Ok(*val),
+            PacketData::SubPackets(sub_packets) => {
+                let values = sub_packets
                     .iter()
                     .map(|sub_packet| sub_packet.eval())
-                    .min()
-                    .unwrap(),
-                3 => sub_packets
-                    .iter()
-                    .map(|sub_packet| sub_packet.eval())
-                    .max()
-                    .unwrap(),
-                5 => {
-                    if sub_packets[0].eval() > sub_packets[1].eval() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                6 => {
-                    if sub_packets[0].eval() < sub_packets[1].eval() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                7 => {
-                    if sub_packets[0].eval() == sub_packets[1].eval() {
-                        1
-                    } else {
-                        0
-                    }
+                    .collect::<Result<Vec<u64>, _>>()?;
+                let mut iter = values.iter().copied();
+                match (self.type_id, values.as_slice()) {
+                    (0, _) => iter
+                        .try_fold(0u64, |acc, v| acc.checked_add(v))
+                        .ok_or_else(overflow),
+                    (1, _) => iter
+                        .try_fold(1u64, |acc, v| acc.checked_mul(v))
+                        .ok_or_else(overflow),
+                    (2, _) => iter.min().ok_or_else(invalid),
+                    (3, _) => iter.max().ok_or_else(invalid),
+                    (5, [a, b]) => Ok(if a > b { 1 } else { 0 }),
+                    (6, [a, b]) => Ok(if a < b { 1 } else { 0 }),
+                    (7, [a, b]) => Ok(if a == b { 1 } else { 0 }),
+                    _ => Err(invalid()),
                 }
-                _ => panic!(),
-            },
+            }
         }
     }
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u64 {
-    let line = reader.lines().next().unwrap().unwrap();
-    let nibbles: Vec<u8> = line
-        .bytes()
-        .map(|c| {
-            if (b'0'..=b'9').contains(&c) {
-                c - b'0'
-            } else {
-                c - b'A' + 10
-            }
+pub fn parse<R: BufRead>(reader: R) -> Result<Packet, PuzzleError> {
+    let line = lines(reader).expect("a hexadecimal transmission")?;
+    let nibbles = line
+        .span()
+        .chars()
+        .map(|(c, span)| match c.to_digit(16) {
+            Some(n) => Ok(n as u8),
+            None => Err(span.error("a hexadecimal digit")),
         })
-        .collect();
+        .collect::<Result<Vec<u8>, _>>()?;
 
     let mut biterator = Biterator::new(nibbles.as_slice());
-    let packet = Packet::load(&mut biterator).unwrap();
-    packet.version_sum()
+    Packet::load(&mut biterator, 0).map_err(|e| match e {
+        LoadError::Truncated => Span {
+            line: line.number,
+            column: line.text.len() + 1,
+            text: "",
+        }
+        .error("the rest of a packet"),
+        LoadError::TooDeep(bit) => {
+            // Every character is a hexadecimal digit holding four bits.
+            let (_, rest) = line.span().split_at(bit / 4);
+            rest.split_at(1)
+                .0
+                .error(format!("packets nested at most {} deep", MAX_DEPTH))
+        }
+    })
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u64 {
-    let line = reader.lines().next().unwrap().unwrap();
-    let nibbles: Vec<u8> = line
-        .bytes()
-        .map(|c| {
-            if (b'0'..=b'9').contains(&c) {
-                c - b'0'
-            } else {
-                c - b'A' + 10
-            }
-        })
-        .collect();
+pub fn part1(packet: &Packet) -> Result<u64, PuzzleError> {
+    Ok(packet.version_sum())
+}
 
-    let mut biterator = Biterator::new(nibbles.as_slice());
-    let packet = Packet::load(&mut biterator).unwrap();
+pub fn part2(packet: &Packet) -> Result<u64, PuzzleError> {
     packet.eval()
 }
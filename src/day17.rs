@@ -1,12 +1,27 @@
+use crate::error::PuzzleError;
+use crate::params::params;
+use crate::parse::{lines, Span};
+use crate::trace;
 use crossbeam_channel::{unbounded, Sender};
 use legion::systems::CommandBuffer;
 use legion::{system, Entity, IntoQuery, Resources, Schedule, World};
 use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+params! {
+    /// The initial velocities launched by each part.
+    pub struct Params {
+        pub part1_x: RangeInclusive<i16> = 0..=200,
+        pub part1_y: RangeInclusive<i16> = -100..=500,
+        pub part2_x: RangeInclusive<i16> = 0..=500,
+        pub part2_y: RangeInclusive<i16> = -200..=1000,
+    }
+}
 
-#[derive(Debug, Copy, Clone)]
-struct Position {
-    x: i16,
-    y: i16,
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Position {
+    pub x: i16,
+    pub y: i16,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -18,20 +33,17 @@ struct Velocity {
 #[derive(Debug, Copy, Clone)]
 struct MaxYPos(i16);
 
-#[derive(Debug, Copy, Clone)]
-struct InitialVelocity(Velocity);
-
-#[derive(Debug, Copy, Clone)]
-struct TargetArea {
-    p1: Position,
-    p2: Position,
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TargetArea {
+    pub p1: Position,
+    pub p2: Position,
 }
 
 #[derive(Debug)]
-struct ProbesReachedTargetArea(Sender<(MaxYPos, InitialVelocity)>);
+struct ProbesReachedTargetArea(Sender<MaxYPos>);
 
 impl TargetArea {
-    fn contains(&self, pos: Position) -> bool {
+    pub fn contains(&self, pos: Position) -> bool {
         (self.p1.x..=self.p2.x).contains(&pos.x) && (self.p1.y..=self.p2.y).contains(&pos.y)
     }
 }
@@ -59,17 +71,13 @@ fn max_y_pos(pos: &Position, max_y_pos: &mut MaxYPos) {
 fn probe_reached_target_area(
     entity: &Entity,
     pos: &Position,
-    initial_velocity: &InitialVelocity,
     max_y_pos: &MaxYPos,
     #[resource] target_area: &TargetArea,
     #[resource] probes_reached_target_area: &ProbesReachedTargetArea,
     buffer: &mut CommandBuffer,
 ) {
     if target_area.contains(*pos) {
-        probes_reached_target_area
-            .0
-            .send((*max_y_pos, *initial_velocity))
-            .unwrap();
+        probes_reached_target_area.0.send(*max_y_pos).unwrap();
         buffer.remove(*entity);
     }
 }
@@ -81,37 +89,42 @@ fn delete_entities(entity: &Entity, pos: &Position, buffer: &mut CommandBuffer)
     }
 }
 
-fn load_target_area<R: BufRead>(reader: R) -> TargetArea {
-    let line = reader.lines().next().unwrap().unwrap();
-    let mut parts = line.split(' ');
-    parts.next();
-    parts.next();
-    let x_text = parts.next().unwrap();
-    let y_text = parts.next().unwrap();
-
-    fn parse_range(text: &str) -> (i16, i16) {
-        let (_, range) = text.split_once('=').unwrap();
-        let (a, b) = range.split_once("..").unwrap();
-        (
-            a.parse().unwrap(),
-            b.split(',').next().unwrap().parse().unwrap(),
-        )
+pub fn parse<R: BufRead>(reader: R) -> Result<TargetArea, PuzzleError> {
+    const EXPECTED: &str = "a target area `target area: x=X1..X2, y=Y1..Y2`";
+    let line = lines(reader).expect(EXPECTED)?;
+    let ranges = line.span().strip_prefix("target area: ", EXPECTED)?;
+    let (x_text, y_text) = ranges.split_once(", ", EXPECTED)?;
+
+    fn parse_range(text: Span, prefix: &str) -> Result<(i16, i16), PuzzleError> {
+        let expected = format!("a range `{}A..B`", prefix);
+        let range = text.strip_prefix(prefix, &expected)?;
+        let (a, b) = range.split_once("..", &expected)?;
+        let a = a.parse("an integer")?;
+        let b = b.parse("an integer")?;
+        if a > b {
+            return Err(range.error(format!("a range `{}A..B` with A <= B", prefix)));
+        }
+        Ok((a, b))
     }
 
-    let (x1, x2) = parse_range(x_text);
-    let (y1, y2) = parse_range(y_text);
+    let (x1, x2) = parse_range(x_text, "x=")?;
+    let (y1, y2) = parse_range(y_text, "y=")?;
 
-    TargetArea {
+    Ok(TargetArea {
         p1: Position { x: x1, y: y1 },
         p2: Position { x: x2, y: y2 },
-    }
+    })
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> i16 {
+/// Launches a probe for every velocity in `xs` × `ys` and collects the ones that hit.
+fn launch(
+    target_area: &TargetArea,
+    xs: RangeInclusive<i16>,
+    ys: RangeInclusive<i16>,
+) -> Vec<MaxYPos> {
     let mut world = World::default();
     let mut resources = Resources::default();
-    resources.insert(load_target_area(reader));
+    resources.insert(*target_area);
 
     let (sender, receiver) = unbounded();
     resources.insert(ProbesReachedTargetArea(sender));
@@ -125,68 +138,42 @@ pub fn part1<R: BufRead>(reader: R) -> i16 {
         .add_system(delete_entities_system())
         .build();
 
-    for x in 0..=200 {
-        for y in -100..=500 {
-            world.push((
-                Position { x: 0, y: 0 },
-                Velocity { x, y },
-                InitialVelocity(Velocity { x, y }),
-                MaxYPos(0),
-            ));
+    let spawn = trace::span("spawn probes");
+    for x in xs {
+        for y in ys.clone() {
+            world.push((Position { x: 0, y: 0 }, Velocity { x, y }, MaxYPos(0)));
         }
     }
+    drop(spawn);
 
     let mut query = <&Velocity>::query();
 
     while query.iter(&world).next().is_some() {
+        let _tick = trace::span("tick");
         schedule.execute(&mut world, &mut resources);
     }
 
     drop(resources);
 
-    receiver
-        .into_iter()
-        .map(|(max_y_pos, _)| max_y_pos.0)
-        .max()
-        .unwrap()
+    receiver.into_iter().collect()
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> usize {
-    let mut world = World::default();
-    let mut resources = Resources::default();
-    resources.insert(load_target_area(reader));
-
-    let (sender, receiver) = unbounded();
-    resources.insert(ProbesReachedTargetArea(sender));
-
-    let mut schedule = Schedule::builder()
-        .add_system(movement_system())
-        .flush()
-        .add_system(max_y_pos_system())
-        .flush()
-        .add_system(probe_reached_target_area_system())
-        .add_system(delete_entities_system())
-        .build();
-
-    for x in 0..=500 {
-        for y in -200..=1000 {
-            world.push((
-                Position { x: 0, y: 0 },
-                Velocity { x, y },
-                InitialVelocity(Velocity { x, y }),
-                MaxYPos(0),
-            ));
-        }
-    }
-
-    let mut query = <&Velocity>::query();
+pub fn part1(target_area: &TargetArea) -> Result<i16, PuzzleError> {
+    part1_with(target_area, &Params::default())
+}
 
-    while query.iter(&world).next().is_some() {
-        schedule.execute(&mut world, &mut resources);
-    }
+pub fn part1_with(target_area: &TargetArea, params: &Params) -> Result<i16, PuzzleError> {
+    launch(target_area, params.part1_x.clone(), params.part1_y.clone())
+        .into_iter()
+        .map(|max_y_pos| max_y_pos.0)
+        .max()
+        .ok_or_else(|| PuzzleError::no_solution("no probe reaches the target area"))
+}
 
-    drop(resources);
+pub fn part2(target_area: &TargetArea) -> Result<usize, PuzzleError> {
+    part2_with(target_area, &Params::default())
+}
 
-    receiver.into_iter().count()
+pub fn part2_with(target_area: &TargetArea, params: &Params) -> Result<usize, PuzzleError> {
+    Ok(launch(target_area, params.part2_x.clone(), params.part2_y.clone()).len())
 }
@@ -1,105 +1,715 @@
+use crate::error::PuzzleError;
+use crate::parse::{lines, Lines, Span};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
 use std::io::BufRead;
-use std::ops::Add;
+use std::rc::Rc;
+use std::str::FromStr;
 
-struct Pos {
-    x: u32,
-    y: u32,
+/// A way of following instructions, holding wherever they have taken the submarine so far.
+pub trait NavigationModel: fmt::Debug {
+    /// The name the model goes by, such as `simple`.
+    fn name(&self) -> &'static str;
+
+    /// Follows `ins`, or fails with the reason if the model cannot, leaving the position as it
+    /// was.
+    fn step(&mut self, ins: Ins) -> Result<(), &'static str>;
+
+    /// The horizontal position and the depth.
+    fn position(&self) -> (i64, i64);
+
+    /// The aim, for models that have one.
+    fn aim(&self) -> i64 {
+        0
+    }
 }
 
-impl Default for Pos {
-    fn default() -> Self {
-        Pos { x: 0, y: 0 }
+impl<M: NavigationModel + ?Sized> NavigationModel for Box<M> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn step(&mut self, ins: Ins) -> Result<(), &'static str> {
+        (**self).step(ins)
+    }
+
+    fn position(&self) -> (i64, i64) {
+        (**self).position()
+    }
+
+    fn aim(&self) -> i64 {
+        (**self).aim()
     }
 }
 
-impl Add<Ins> for Pos {
-    type Output = Pos;
+const OVERFLOW: &str = "would move further than a position can hold";
 
-    fn add(self, ins: Ins) -> Self::Output {
+/// The simple model of part 1, where `down` and `up` change the depth.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Pos {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl NavigationModel for Pos {
+    fn name(&self) -> &'static str {
+        "simple"
+    }
+
+    fn step(&mut self, ins: Ins) -> Result<(), &'static str> {
         match ins {
-            Ins::Forward(f) => Pos {
-                x: self.x + f,
-                ..self
-            },
-            Ins::Down(f) => Pos {
-                y: self.y + f,
-                ..self
-            },
-            Ins::Up(f) => Pos {
-                y: self.y - f,
-                ..self
-            },
+            Ins::Forward(f) => self.x = self.x.checked_add(f).ok_or(OVERFLOW)?,
+            Ins::Back(f) => {
+                self.x = self
+                    .x
+                    .checked_sub(f)
+                    .ok_or("would back up past the start")?
+            }
+            Ins::Down(f) => self.y = self.y.checked_add(f).ok_or(OVERFLOW)?,
+            Ins::Up(f) => {
+                self.y = self
+                    .y
+                    .checked_sub(f)
+                    .ok_or("would rise above the surface")?
+            }
         }
+        Ok(())
+    }
+
+    fn position(&self) -> (i64, i64) {
+        (self.x as i64, self.y as i64)
+    }
+}
+
+/// The simple model with a signed depth, which can follow the submarine above the surface.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SignedPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl NavigationModel for SignedPos {
+    fn name(&self) -> &'static str {
+        "signed"
+    }
+
+    fn step(&mut self, ins: Ins) -> Result<(), &'static str> {
+        let f = i32::try_from(ins.distance()).map_err(|_| OVERFLOW)?;
+        let moved = match ins {
+            Ins::Forward(_) => self.x.checked_add(f).map(|x| SignedPos { x, ..*self }),
+            Ins::Back(_) => self.x.checked_sub(f).map(|x| SignedPos { x, ..*self }),
+            Ins::Down(_) => self.y.checked_add(f).map(|y| SignedPos { y, ..*self }),
+            Ins::Up(_) => self.y.checked_sub(f).map(|y| SignedPos { y, ..*self }),
+        };
+        *self = moved.ok_or(OVERFLOW)?;
+        Ok(())
+    }
+
+    fn position(&self) -> (i64, i64) {
+        (self.x as i64, self.y as i64)
+    }
+}
+
+/// The model of part 2, where `down` and `up` change the aim and `forward` dives along it.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Pos2 {
+    pub x: i32,
+    pub y: i32,
+    pub aim: i32,
+}
+
+impl NavigationModel for Pos2 {
+    fn name(&self) -> &'static str {
+        "aim"
+    }
+
+    fn step(&mut self, ins: Ins) -> Result<(), &'static str> {
+        let f = i32::try_from(ins.distance()).map_err(|_| OVERFLOW)?;
+        let moved = match ins {
+            Ins::Forward(_) => self
+                .aim
+                .checked_mul(f)
+                .and_then(|dy| self.y.checked_add(dy))
+                .zip(self.x.checked_add(f))
+                .map(|(y, x)| Pos2 { x, y, ..*self }),
+            Ins::Back(_) => self
+                .aim
+                .checked_mul(f)
+                .and_then(|dy| self.y.checked_sub(dy))
+                .zip(self.x.checked_sub(f))
+                .map(|(y, x)| Pos2 { x, y, ..*self }),
+            Ins::Down(_) => self.aim.checked_add(f).map(|aim| Pos2 { aim, ..*self }),
+            Ins::Up(_) => self.aim.checked_sub(f).map(|aim| Pos2 { aim, ..*self }),
+        };
+        *self = moved.ok_or(OVERFLOW)?;
+        Ok(())
+    }
+
+    fn position(&self) -> (i64, i64) {
+        (self.x as i64, self.y as i64)
+    }
+
+    fn aim(&self) -> i64 {
+        self.aim as i64
     }
 }
 
-enum Ins {
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Ins {
     Forward(u32),
+    Back(u32),
     Down(u32),
     Up(u32),
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let pos = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| {
-            let d: Vec<&str> = line.split_whitespace().collect();
-            match d.as_slice() {
-                ["forward", f] => Ins::Forward(f.parse::<u32>().unwrap()),
-                ["down", f] => Ins::Down(f.parse::<u32>().unwrap()),
-                ["up", f] => Ins::Up(f.parse::<u32>().unwrap()),
-                _ => panic!(),
+impl Ins {
+    pub fn distance(self) -> u32 {
+        match self {
+            Ins::Forward(f) | Ins::Back(f) | Ins::Down(f) | Ins::Up(f) => f,
+        }
+    }
+}
+
+impl fmt::Display for Ins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ins::Forward(d) => write!(f, "forward {}", d),
+            Ins::Back(d) => write!(f, "back {}", d),
+            Ins::Down(d) => write!(f, "down {}", d),
+            Ins::Up(d) => write!(f, "up {}", d),
+        }
+    }
+}
+
+const EXPECTED: &str = "`forward`, `back`, `down` or `up` followed by a distance";
+
+fn instruction(ins: Span, distance: Span) -> Result<Ins, PuzzleError> {
+    let make = match ins.text {
+        "forward" => Ins::Forward,
+        "back" => Ins::Back,
+        "down" => Ins::Down,
+        "up" => Ins::Up,
+        _ => return Err(ins.error(EXPECTED)),
+    };
+    Ok(make(distance.parse("a distance")?))
+}
+
+/// Reads one instruction per line, as in the puzzle input, for any model to follow.
+pub fn commands<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Command, PuzzleError>> {
+    lines(reader).map(|line| {
+        let line = line?;
+        let d: Vec<_> = line.span().split_whitespace().collect();
+        match d.as_slice() {
+            [ins, f] => Ok(Command {
+                line: line.number,
+                called: None,
+                ins: instruction(*ins, *f)?,
+            }),
+            _ => Err(line.span().error(EXPECTED)),
+        }
+    })
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Ins>, PuzzleError> {
+    commands(reader).map(|command| Ok(command?.ins)).collect()
+}
+
+/// Parsed instructions as the commands on each line of the input.
+fn numbered(instructions: &[Ins]) -> impl Iterator<Item = Command> + '_ {
+    instructions.iter().zip(1..).map(|(&ins, line)| Command {
+        line,
+        called: None,
+        ins,
+    })
+}
+
+/// Follows `commands` under `model`, failing at the first one it cannot.
+pub fn navigate<M: NavigationModel>(
+    mut model: M,
+    commands: impl IntoIterator<Item = Result<Command, PuzzleError>>,
+) -> Result<M, PuzzleError> {
+    for command in commands {
+        let command = command?;
+        model.step(command.ins).map_err(|e| command.error(e))?;
+    }
+    Ok(model)
+}
+
+/// Where a model ended up in a [`compare`]d run.
+#[derive(Debug)]
+pub struct Outcome {
+    pub model: Box<dyn NavigationModel>,
+    /// Why the model stopped before the last command, if it did.
+    pub error: Option<PuzzleError>,
+}
+
+/// Follows `commands` under all of `models` in one pass. A model that cannot follow a command
+/// stops there while the rest go on, but an error reading the commands fails the run.
+pub fn compare(
+    models: Vec<Box<dyn NavigationModel>>,
+    commands: impl IntoIterator<Item = Result<Command, PuzzleError>>,
+) -> Result<Vec<Outcome>, PuzzleError> {
+    let mut outcomes: Vec<Outcome> = models
+        .into_iter()
+        .map(|model| Outcome { model, error: None })
+        .collect();
+    for command in commands {
+        let command = command?;
+        for outcome in outcomes.iter_mut().filter(|o| o.error.is_none()) {
+            if let Err(e) = outcome.model.step(command.ins) {
+                outcome.error = Some(command.error(e));
             }
-        })
-        .fold(Pos::default(), |p, i| p + i);
-    pos.x * pos.y
+        }
+    }
+    Ok(outcomes)
 }
 
-#[derive(Default)]
-struct Pos2 {
-    x: i32,
-    y: i32,
-    aim: i32,
+/// The puzzle's answer, the final horizontal position multiplied by the final depth, if it fits.
+fn product<T>(product: Option<T>) -> Result<T, PuzzleError> {
+    product.ok_or_else(|| {
+        PuzzleError::no_solution("the horizontal position times the depth is too large")
+    })
 }
 
-impl Add<Ins> for Pos2 {
-    type Output = Pos2;
+pub fn part1(instructions: &[Ins]) -> Result<u32, PuzzleError> {
+    let pos = navigate(Pos::default(), numbered(instructions).map(Ok))?;
+    product(pos.x.checked_mul(pos.y))
+}
 
-    fn add(self, ins: Ins) -> Self::Output {
-        match ins {
-            Ins::Forward(f) => Pos2 {
-                x: self.x + f as i32,
-                y: self.y + self.aim * f as i32,
-                ..self
-            },
-            Ins::Down(f) => Pos2 {
-                aim: self.aim + f as i32,
-                ..self
-            },
-            Ins::Up(f) => Pos2 {
-                aim: self.aim - f as i32,
-                ..self
+pub fn part2(instructions: &[Ins]) -> Result<i32, PuzzleError> {
+    let pos = navigate(Pos2::default(), numbered(instructions).map(Ok))?;
+    product(pos.x.checked_mul(pos.y))
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<u32, PuzzleError> {
+    let pos = navigate(Pos::default(), commands(reader))?;
+    product(pos.x.checked_mul(pos.y))
+}
+
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<i32, PuzzleError> {
+    let pos = navigate(Pos2::default(), commands(reader))?;
+    product(pos.x.checked_mul(pos.y))
+}
+
+/// How a script's instructions move the submarine: `Simple` follows part 1, where `down` and
+/// `up` change the depth, `Signed` does the same but can rise above the surface, and `Aim`
+/// follows part 2, where they change the aim.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Model {
+    Simple,
+    Signed,
+    Aim,
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simple" => Ok(Model::Simple),
+            "signed" => Ok(Model::Signed),
+            "aim" => Ok(Model::Aim),
+            _ => Err(format!(
+                "unknown model {:?}, expected `simple`, `signed` or `aim`",
+                s
+            )),
+        }
+    }
+}
+
+impl Model {
+    /// The model's starting position.
+    pub fn start(self) -> Box<dyn NavigationModel> {
+        match self {
+            Model::Simple => Box::new(Pos::default()),
+            Model::Signed => Box::new(SignedPos::default()),
+            Model::Aim => Box::new(Pos2::default()),
+        }
+    }
+}
+
+/// An instruction in a script and the line it was written on. Instructions run by a macro also
+/// remember the line of the call that ran them, the outermost one if macros call macros.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Command {
+    pub line: usize,
+    pub called: Option<usize>,
+    pub ins: Ins,
+}
+
+impl Command {
+    /// Where the command ran, such as `line 12`, or `line 12 (macro line 3)` for a command
+    /// in a macro called on line 12.
+    pub fn location(&self) -> String {
+        match self.called {
+            Some(call) => format!("line {} (macro line {})", call, self.line),
+            None => format!("line {}", self.line),
+        }
+    }
+
+    fn error(&self, reason: &str) -> PuzzleError {
+        PuzzleError::no_solution(format!("{}: `{}` {}", self.location(), self.ins, reason))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Statement {
+    Command(Command),
+    Repeat(u32, Vec<Statement>),
+    /// A macro call, on the given line, sharing the macro's body with every other call.
+    Call(usize, Rc<[Statement]>),
+}
+
+/// A list of submarine instructions written in a small command language. Besides the puzzle's
+/// `forward`, `down` and `up`, a script may use:
+///
+/// - `back N`, which undoes `forward N`;
+/// - comments, from `#` to the end of the line;
+/// - `repeat N {` ... `}`, which runs the lines between the braces `N` times;
+/// - `macro NAME {` ... `}` at the top level, after which a line holding just `NAME` runs the
+///   lines between the braces.
+///
+/// Every puzzle input is a script.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    body: Vec<Statement>,
+}
+
+impl Script {
+    pub fn parse<R: BufRead>(reader: R) -> Result<Script, PuzzleError> {
+        let body = block(&mut lines(reader), &mut HashMap::new(), None)?;
+        Ok(Script { body })
+    }
+
+    /// The instructions the script runs, in order, with repeats and macros expanded.
+    pub fn commands(&self) -> Commands<'_> {
+        Commands {
+            stack: vec![Block::new(&self.body, 0, None)],
+        }
+    }
+
+    /// Where the script leaves the submarine, starting from `model`.
+    pub fn run<M: NavigationModel>(&self, model: M) -> Result<M, PuzzleError> {
+        navigate(model, self.commands().map(Ok))
+    }
+
+    /// Every position the script takes the submarine through under `model`.
+    pub fn trajectory<M: NavigationModel>(&self, model: M) -> Result<Trajectory, PuzzleError> {
+        Trajectory::new(model, self.commands())
+    }
+
+    /// The final horizontal position multiplied by the final depth, as the puzzle asks for.
+    pub fn solve(&self, model: Model) -> Result<i64, PuzzleError> {
+        let (x, depth) = self.run(model.start())?.position();
+        product(x.checked_mul(depth))
+    }
+}
+
+const KEYWORDS: [&str; 6] = ["forward", "back", "down", "up", "repeat", "macro"];
+
+fn open_brace(span: Span) -> Result<(), PuzzleError> {
+    match span.text {
+        "{" => Ok(()),
+        _ => Err(span.error("`{`")),
+    }
+}
+
+/// Reads statements up to the `}` closing the block opened on line `opened`, or to the end of
+/// the input at the top level.
+fn block<R: BufRead>(
+    lines: &mut Lines<R>,
+    macros: &mut HashMap<String, Rc<[Statement]>>,
+    opened: Option<usize>,
+) -> Result<Vec<Statement>, PuzzleError> {
+    let mut body = Vec::new();
+    while let Some(line) = lines.next() {
+        let line = line?;
+        let mut code = line.span();
+        if let Some(comment) = code.text.find('#') {
+            code = code.split_at(comment).0;
+        }
+        let words: Vec<_> = code.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            [close] if close.text == "}" => match opened {
+                Some(_) => return Ok(body),
+                None => return Err(close.error("a command outside any block")),
             },
+            [keyword, count, open] if keyword.text == "repeat" => {
+                let count = count.parse("a repeat count")?;
+                open_brace(*open)?;
+                let repeated = block(lines, macros, Some(line.number))?;
+                body.push(Statement::Repeat(count, repeated));
+            }
+            [keyword, name, open] if keyword.text == "macro" => {
+                if opened.is_some() {
+                    return Err(keyword.error("a command, as macros are defined outside blocks"));
+                }
+                let valid = name.text.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && name
+                        .text
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+                if !valid || KEYWORDS.contains(&name.text) || macros.contains_key(name.text) {
+                    return Err(name.error("a new macro name"));
+                }
+                open_brace(*open)?;
+                let defined = block(lines, macros, Some(line.number))?;
+                macros.insert(name.text.to_string(), defined.into());
+            }
+            [name] if macros.contains_key(name.text) => {
+                body.push(Statement::Call(line.number, macros[name.text].clone()));
+            }
+            [ins, distance] if KEYWORDS[..4].contains(&ins.text) => {
+                body.push(Statement::Command(Command {
+                    line: line.number,
+                    called: None,
+                    ins: instruction(*ins, *distance)?,
+                }));
+            }
+            [first, ..] => {
+                return Err(first
+                    .error("an instruction, `repeat N {`, `macro NAME {`, `}` or a defined macro"))
+            }
+        }
+    }
+    match opened {
+        Some(opened) => Err(PuzzleError::UnexpectedEof {
+            line: lines.number() + 1,
+            expected: format!("`}}` closing the block opened on line {}", opened),
+        }),
+        None => Ok(body),
+    }
+}
+
+/// The iterator returned by [`Script::commands`].
+pub struct Commands<'a> {
+    /// The blocks being run, innermost last.
+    stack: Vec<Block<'a>>,
+}
+
+struct Block<'a> {
+    statements: std::slice::Iter<'a, Statement>,
+    body: &'a [Statement],
+    /// How many more times the block runs after this time.
+    remaining: u32,
+    /// The line of the outermost macro call running the block.
+    called: Option<usize>,
+}
+
+impl<'a> Block<'a> {
+    fn new(body: &'a [Statement], remaining: u32, called: Option<usize>) -> Self {
+        Block {
+            statements: body.iter(),
+            body,
+            remaining,
+            called,
         }
     }
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> i32 {
-    let pos = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| {
-            let d: Vec<&str> = line.split_whitespace().collect();
-            match d.as_slice() {
-                ["forward", f] => Ins::Forward(f.parse().unwrap()),
-                ["down", f] => Ins::Down(f.parse().unwrap()),
-                ["up", f] => Ins::Up(f.parse().unwrap()),
-                _ => panic!(),
+impl Iterator for Commands<'_> {
+    type Item = Command;
+
+    fn next(&mut self) -> Option<Command> {
+        loop {
+            let block = self.stack.last_mut()?;
+            let called = block.called;
+            match block.statements.next() {
+                Some(Statement::Command(command)) => return Some(Command { called, ..*command }),
+                Some(Statement::Repeat(count, repeated)) => {
+                    if *count > 0 && !repeated.is_empty() {
+                        self.stack.push(Block::new(repeated, count - 1, called));
+                    }
+                }
+                Some(Statement::Call(line, body)) => {
+                    self.stack.push(Block::new(body, 0, called.or(Some(*line))));
+                }
+                None if block.remaining > 0 => {
+                    block.remaining -= 1;
+                    block.statements = block.body.iter();
+                }
+                None => {
+                    self.stack.pop();
+                }
             }
+        }
+    }
+}
+
+/// Where the submarine is after one command. Models without an aim leave it at 0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub command: Command,
+    pub pos: Pos2,
+}
+
+/// Every position a list of commands takes the submarine through. Follow [`SignedPos`] rather
+/// than [`Pos`] to see where it goes above the surface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    /// The [`NavigationModel::name`] of the model followed.
+    pub model: &'static str,
+    pub steps: Vec<Step>,
+}
+
+/// Every position the puzzle's instructions take the submarine through under `model`.
+pub fn trajectory<M: NavigationModel>(
+    instructions: &[Ins],
+    model: M,
+) -> Result<Trajectory, PuzzleError> {
+    Trajectory::new(model, numbered(instructions))
+}
+
+impl Trajectory {
+    /// Follows `commands` from wherever `model` is. Fails if the model cannot follow a command,
+    /// or a position no longer fits in an `i32`.
+    pub fn new<M: NavigationModel>(
+        mut model: M,
+        commands: impl IntoIterator<Item = Command>,
+    ) -> Result<Trajectory, PuzzleError> {
+        let mut steps = Vec::new();
+        for command in commands {
+            model.step(command.ins).map_err(|e| command.error(e))?;
+            let (x, y) = model.position();
+            let pos = (x.try_into(), y.try_into(), model.aim().try_into());
+            let (Ok(x), Ok(y), Ok(aim)) = pos else {
+                return Err(command.error(OVERFLOW));
+            };
+            steps.push(Step {
+                command,
+                pos: Pos2 { x, y, aim },
+            });
+        }
+        Ok(Trajectory {
+            model: model.name(),
+            steps,
         })
-        .fold(Pos2::default(), |p, i| p + i);
-    pos.x * pos.y
+    }
+
+    /// The start and then the position after each command.
+    pub fn states(&self) -> impl Iterator<Item = Pos2> + '_ {
+        std::iter::once(Pos2::default()).chain(self.steps.iter().map(|step| step.pos))
+    }
+
+    /// Where the submarine ends up.
+    pub fn end(&self) -> Pos2 {
+        self.steps.last().map_or(Pos2::default(), |step| step.pos)
+    }
+
+    /// The steps that take the submarine from the surface or below to above it.
+    pub fn surfacings(&self) -> impl Iterator<Item = &Step> + '_ {
+        self.states()
+            .zip(&self.steps)
+            .filter(|(before, step)| before.y >= 0 && step.pos.y < 0)
+            .map(|(_, step)| step)
+    }
+
+    /// One line per state, after a `step,line,called,command,x,depth,aim` header. `called` is
+    /// the line of the macro call that ran the command, and is empty outside macros, as in
+    /// [`Command::location`]. The start is step 0, with no line or command.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("step,line,called,command,x,depth,aim\n");
+        writeln!(out, "0,,,,0,0,0").unwrap();
+        for (i, step) in self.steps.iter().enumerate() {
+            let Step { command, pos } = step;
+            let called = command
+                .called
+                .map(|call| call.to_string())
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{}",
+                i + 1,
+                command.line,
+                called,
+                command.ins,
+                pos.x,
+                pos.y,
+                pos.aim
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// A plot of depth against horizontal position, deeper further down, with the surface
+    /// dashed and every surfacing circled.
+    pub fn to_svg(&self) -> String {
+        const WIDTH: f64 = 800.0;
+        const HEIGHT: f64 = 400.0;
+        const MARGIN: f64 = 40.0;
+
+        let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+        for pos in self.states() {
+            left = left.min(pos.x);
+            right = right.max(pos.x);
+            top = top.min(pos.y);
+            bottom = bottom.max(pos.y);
+        }
+        let scale = |value: i32, low: i32, high: i32, size: f64| {
+            let range = (high as f64 - low as f64).max(1.0);
+            MARGIN + (value as f64 - low as f64) / range * (size - 2.0 * MARGIN)
+        };
+        let sx = |x| scale(x, left, right, WIDTH);
+        let sy = |y| scale(y, top, bottom, HEIGHT);
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = WIDTH,
+            h = HEIGHT
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r#"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="steelblue" stroke-dasharray="4 4"/>"#,
+            MARGIN,
+            WIDTH - MARGIN,
+            y = sy(0)
+        )
+        .unwrap();
+        let points: Vec<String> = self
+            .states()
+            .map(|pos| format!("{:.1},{:.1}", sx(pos.x), sy(pos.y)))
+            .collect();
+        writeln!(
+            out,
+            r#"<polyline fill="none" stroke="black" points="{}"/>"#,
+            points.join(" ")
+        )
+        .unwrap();
+        for step in self.surfacings() {
+            writeln!(
+                out,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="none" stroke="red"><title>{}: {}</title></circle>"#,
+                sx(step.pos.x),
+                sy(step.pos.y),
+                step.command.location(),
+                step.command.ins
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            r#"<text x="{:.1}" y="{:.1}" font-size="12">depth {}</text>"#,
+            MARGIN,
+            MARGIN - 8.0,
+            top
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r#"<text x="{:.1}" y="{:.1}" font-size="12">depth {}, horizontal position {} to {}</text>"#,
+            MARGIN,
+            HEIGHT - MARGIN + 20.0,
+            bottom,
+            left,
+            right
+        )
+        .unwrap();
+        out.push_str("</svg>\n");
+        out
+    }
 }
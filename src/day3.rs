@@ -1,10 +1,43 @@
+use crate::error::PuzzleError;
+use crate::parse::lines;
 use std::io::BufRead;
 
-fn str_to_bool_vec(text: &str) -> Vec<bool> {
-    text.chars().map(|c| c == '1').collect::<Vec<_>>()
+fn rows<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Vec<bool>, PuzzleError>> {
+    let mut width = None;
+    lines(reader).map(move |line| {
+        let line = line?;
+        if let Some(width) = width {
+            if width != line.text.len() {
+                return Err(line
+                    .span()
+                    .error(format!("a binary number of {} bits", width)));
+            }
+        }
+        let bits = line
+            .span()
+            .chars()
+            .map(|(c, span)| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(span.error("a binary digit")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        width = Some(bits.len());
+        Ok(bits)
+    })
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Vec<bool>>, PuzzleError> {
+    rows(reader).collect()
 }
 
-fn bits_to_u32(bits: Vec<bool>) -> u32 {
+fn bits_to_u32(bits: Vec<bool>) -> Result<u32, PuzzleError> {
+    if bits.len() > 32 {
+        return Err(PuzzleError::no_solution(format!(
+            "numbers of {} bits are too wide",
+            bits.len()
+        )));
+    }
     let mut n = 0;
     for b in bits {
         n <<= 1;
@@ -12,75 +45,148 @@ fn bits_to_u32(bits: Vec<bool>) -> u32 {
             n += 1;
         }
     }
-    n
+    Ok(n)
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let (count, pip_count) = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| str_to_bool_vec(line.as_str()))
-        .fold((0, Vec::new()), |(count, mut tot), n| {
-            while tot.len() < n.len() {
-                tot.push(0);
-            }
+/// Multiplies two ratings, which always fit in a `u64` as they are at most 32 bits wide.
+fn multiply(a: u32, b: u32) -> Result<u64, PuzzleError> {
+    u64::from(a)
+        .checked_mul(b.into())
+        .ok_or_else(|| PuzzleError::no_solution("the ratings multiply to too large a number"))
+}
 
-            for (p, v) in tot.iter_mut().zip(n) {
-                if v {
-                    *p += 1;
-                }
-            }
+/// How many of the numbers seen so far have a one in each position.
+#[derive(Default)]
+struct OneCounts {
+    count: usize,
+    ones: Vec<usize>,
+}
 
-            (count + 1, tot)
-        });
+impl OneCounts {
+    fn add(&mut self, bits: &[bool]) {
+        while self.ones.len() < bits.len() {
+            self.ones.push(0);
+        }
+        for (p, v) in self.ones.iter_mut().zip(bits) {
+            if *v {
+                *p += 1;
+            }
+        }
+        self.count += 1;
+    }
 
-    let gamma_bits: Vec<bool> = pip_count.into_iter().map(|c| c > count / 2).collect();
-    let epsilon_bits: Vec<bool> = gamma_bits.iter().copied().map(|b| !b).collect();
+    fn power_consumption(&self) -> Result<u64, PuzzleError> {
+        let gamma_bits: Vec<bool> = self.ones.iter().map(|c| *c > self.count / 2).collect();
+        let epsilon_bits: Vec<bool> = gamma_bits.iter().copied().map(|b| !b).collect();
+        multiply(bits_to_u32(gamma_bits)?, bits_to_u32(epsilon_bits)?)
+    }
+}
 
-    bits_to_u32(gamma_bits) * bits_to_u32(epsilon_bits)
+pub fn part1(all_bits: &[Vec<bool>]) -> Result<u64, PuzzleError> {
+    let mut ones = OneCounts::default();
+    all_bits.iter().for_each(|bits| ones.add(bits));
+    ones.power_consumption()
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u32 {
-    let all_bits: Vec<Vec<bool>> = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(|line: String| str_to_bool_vec(line.as_str()))
-        .collect();
+pub fn part2(all_bits: &[Vec<bool>]) -> Result<u64, PuzzleError> {
+    let width = all_bits.first().map(Vec::len).unwrap_or(0);
 
-    let mut o2_gen_rating = all_bits.clone();
+    let mut o2_gen_rating = all_bits.to_vec();
     let mut pos = 0;
-    while o2_gen_rating.len() > 1 {
+    while o2_gen_rating.len() > 1 && pos < width {
         let (ones, zeroes) =
             o2_gen_rating.iter().fold(
                 (0, 0),
                 |(o, z), b| if b[pos] { (o + 1, z) } else { (o, z + 1) },
             );
         if ones >= zeroes {
-            o2_gen_rating = o2_gen_rating.into_iter().filter(|r| r[pos]).collect();
+            o2_gen_rating.retain(|r| r[pos]);
         } else {
-            o2_gen_rating = o2_gen_rating.into_iter().filter(|r| !r[pos]).collect();
+            o2_gen_rating.retain(|r| !r[pos]);
         }
         pos += 1;
     }
 
-    let mut co2_scrub_rating = all_bits;
+    let mut co2_scrub_rating = all_bits.to_vec();
     pos = 0;
-    while co2_scrub_rating.len() > 1 {
+    while co2_scrub_rating.len() > 1 && pos < width {
         let (ones, zeroes) =
             co2_scrub_rating.iter().fold(
                 (0, 0),
                 |(o, z), b| if b[pos] { (o + 1, z) } else { (o, z + 1) },
             );
-        if ones < zeroes {
-            co2_scrub_rating = co2_scrub_rating.into_iter().filter(|r| r[pos]).collect();
+        // When every candidate has the same bit, that bit is the least common one too.
+        if (ones < zeroes && ones > 0) || zeroes == 0 {
+            co2_scrub_rating.retain(|r| r[pos]);
         } else {
-            co2_scrub_rating = co2_scrub_rating.into_iter().filter(|r| !r[pos]).collect();
+            co2_scrub_rating.retain(|r| !r[pos]);
         }
         pos += 1;
     }
 
-    bits_to_u32(o2_gen_rating.into_iter().next().unwrap())
-        * bits_to_u32(co2_scrub_rating.into_iter().next().unwrap())
+    match (
+        o2_gen_rating.into_iter().next(),
+        co2_scrub_rating.into_iter().next(),
+    ) {
+        (Some(o2), Some(co2)) => multiply(bits_to_u32(o2)?, bits_to_u32(co2)?),
+        _ => Err(PuzzleError::no_solution("the diagnostic report is empty")),
+    }
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<u64, PuzzleError> {
+    let mut ones = OneCounts::default();
+    for bits in rows(reader) {
+        ones.add(&bits?);
+    }
+    ones.power_consumption()
+}
+
+/// The widest numbers [`part2_streaming`] accepts, as it keeps a counter per possible value.
+pub const MAX_STREAMING_WIDTH: usize = 20;
+
+/// Keeps how often each value occurs rather than the numbers themselves. The numbers sharing a
+/// prefix form a contiguous range of values, so each filtering step halves a range.
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<u64, PuzzleError> {
+    let mut width = 0;
+    let mut histogram: Vec<u64> = Vec::new();
+    for bits in rows(reader) {
+        let bits = bits?;
+        if histogram.is_empty() {
+            width = bits.len();
+            if width > MAX_STREAMING_WIDTH {
+                return Err(PuzzleError::no_solution(format!(
+                    "numbers of {} bits are too wide to stream",
+                    width
+                )));
+            }
+            histogram = vec![0; 1 << width];
+        }
+        histogram[bits_to_u32(bits)? as usize] += 1;
+    }
+
+    let rating = |keep_ones: fn(u64, u64) -> bool| {
+        let (mut lo, mut hi) = (0, histogram.len());
+        for pos in (0..width).rev() {
+            let total: u64 = histogram[lo..hi].iter().sum();
+            if total <= 1 {
+                break;
+            }
+            let mid = lo + (1 << pos);
+            let ones: u64 = histogram[mid..hi].iter().sum();
+            if keep_ones(ones, total - ones) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        (lo..hi).find(|n| histogram[*n] > 0).map(|n| n as u32)
+    };
+
+    match (
+        rating(|o, z| o >= z),
+        rating(|o, z| (o < z && o > 0) || z == 0),
+    ) {
+        (Some(o2), Some(co2)) => multiply(o2, co2),
+        _ => Err(PuzzleError::no_solution("the diagnostic report is empty")),
+    }
 }
@@ -1,31 +1,31 @@
+use crate::error::PuzzleError;
+use crate::parse::{lines, Lines};
 use std::io::BufRead;
 use std::ops::{Index, IndexMut};
 
-struct Board {
+#[derive(Debug, Clone)]
+pub struct Board {
     data: [[u8; 5]; 5],
 }
 
 impl Board {
-    fn load(iter: &mut impl Iterator<Item = String>) -> Self {
+    fn load<R: BufRead>(lines: &mut Lines<R>) -> Result<Self, PuzzleError> {
+        const EXPECTED: &str = "a row of 5 numbers";
         let mut board = Board { data: [[0; 5]; 5] };
-        fn load_line(board: &mut Board, row: usize, iter: &mut impl Iterator<Item = String>) {
-            board.data[row]
-                .iter_mut()
-                .zip(
-                    iter.next()
-                        .unwrap()
-                        .split_whitespace()
-                        .map(|n| n.parse::<u8>().unwrap()),
-                )
-                .for_each(|(pos, num)| *pos = num);
-        }
-        for i in 0..5 {
-            load_line(&mut board, i, iter);
+        for row in board.data.iter_mut() {
+            let line = lines.expect(EXPECTED)?;
+            let nums: Vec<_> = line.span().split_whitespace().collect();
+            if nums.len() != row.len() {
+                return Err(line.span().error(EXPECTED));
+            }
+            for (pos, num) in row.iter_mut().zip(nums) {
+                *pos = num.parse("a number between 0 and 255")?;
+            }
         }
-        board
+        Ok(board)
     }
 
-    fn completion_score(&self, nums: &[u8]) -> Option<u32> {
+    pub fn completion_score(&self, nums: &[u8]) -> Option<u32> {
         let mut complete = false;
         for i in 0..5 {
             if self.data[i].iter().copied().all(|n| nums.contains(&n)) {
@@ -69,52 +69,49 @@ impl IndexMut<(usize, usize)> for Board {
     }
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let mut lines = reader.lines();
-    let nums: Vec<u8> = lines
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse::<u8>().unwrap())
-        .collect();
-
-    let mut lines = lines.map(|res| res.unwrap());
+#[derive(Debug, Clone)]
+pub struct Bingo {
+    pub nums: Vec<u8>,
+    pub boards: Vec<Board>,
+}
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Bingo, PuzzleError> {
+    let mut lines = lines(reader);
+    let nums = lines
+        .expect("the drawn numbers")?
+        .span()
+        .split(",")
+        .map(|n| n.parse("a number between 0 and 255"))
+        .collect::<Result<Vec<u8>, _>>()?;
+
     let mut boards = Vec::new();
-    while let Some(_) = lines.next() {
-        let board = Board::load(&mut lines);
-        boards.push(board);
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if !line.text.is_empty() {
+            return Err(line.span().error("an empty line before the next board"));
+        }
+        boards.push(Board::load(&mut lines)?);
     }
 
+    Ok(Bingo { nums, boards })
+}
+
+pub fn part1(bingo: &Bingo) -> Result<u32, PuzzleError> {
+    let Bingo { nums, boards } = bingo;
+
     for i in 1..nums.len() {
         for b in boards.iter() {
             if let Some(score) = b.completion_score(&nums[..i]) {
-                return score * nums[i - 1] as u32;
+                return Ok(score * nums[i - 1] as u32);
             }
         }
     }
 
-    panic!()
+    Err(PuzzleError::no_solution("no board wins"))
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u32 {
-    let mut lines = reader.lines();
-    let nums: Vec<u8> = lines
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse::<u8>().unwrap())
-        .collect();
-
-    let mut lines = lines.map(|res| res.unwrap());
-    let mut boards = Vec::new();
-    while let Some(_) = lines.next() {
-        let board = Board::load(&mut lines);
-        boards.push(board);
-    }
+pub fn part2(bingo: &Bingo) -> Result<u32, PuzzleError> {
+    let Bingo { nums, boards } = bingo;
 
     let mut has_won = vec![false; boards.len()];
 
@@ -123,11 +120,11 @@ pub fn part2<R: BufRead>(reader: R) -> u32 {
             if let Some(score) = b.completion_score(&nums[..i]) {
                 has_won[bi] = true;
                 if has_won.iter().all(|r| *r) {
-                    return score * nums[i - 1] as u32;
+                    return Ok(score * nums[i - 1] as u32);
                 }
             }
         }
     }
 
-    panic!()
+    Err(PuzzleError::no_solution("not every board wins"))
 }
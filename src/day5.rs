@@ -1,133 +1,79 @@
+use crate::error::PuzzleError;
+use crate::grid::Grid;
+use crate::parse::{lines, Span};
 use std::io::BufRead;
-use std::ops::{Index, IndexMut};
 
-#[derive(Default)]
-struct Board {
-    data: Vec<Vec<u8>>,
-}
-
-impl Board {
-    fn draw_line(&mut self, x1: usize, y1: usize, x2: usize, y2: usize) {
-        self.grow_to_fit(x1, y1);
-        self.grow_to_fit(x2, y2);
-
-        match (x1 == x2, y1 == y2) {
-            (true, false) => {
-                let ymin = usize::min(y1, y2);
-                let ymax = usize::max(y1, y2);
-                for y in ymin..=ymax {
-                    self[(x1, y)] += 1;
-                }
-            }
-            (false, true) => {
-                let xmin = usize::min(x1, x2);
-                let xmax = usize::max(x1, x2);
-                for x in xmin..=xmax {
-                    self[(x, y1)] += 1;
-                }
-            }
-            _ => {
-                let mut p1 = (x1, y1);
-                let mut p2 = (x2, y2);
-                if p1.0 > p2.0 {
-                    std::mem::swap(&mut p1, &mut p2);
-                }
-                let update_point: fn((usize, usize)) -> (usize, usize) = if p1.1 < p2.1 {
-                    |p| (p.0 + 1, p.1 + 1)
-                } else {
-                    |p| (p.0 + 1, p.1 - 1)
-                };
-                while p1 != p2 {
-                    self[p1] += 1;
-                    p1 = update_point(p1);
-                }
-                self[p1] += 1;
-            }
-        }
-    }
-
-    fn grow_to_fit(&mut self, x: usize, y: usize) {
-        while self.data.len() <= x {
-            self.data
-                .push(vec![0; self.data.get(0).map(|r| r.len()).unwrap_or(0)]);
-        }
-
-        while self.data[0].len() <= y {
-            for i in 0..self.data.len() {
-                self.data[i].push(0);
-            }
+fn draw_line(board: &mut Grid<u8>, v: &Vent) {
+    let step = |a: usize, b: usize| (b as isize - a as isize).signum();
+    let (dx, dy) = (step(v.x1, v.x2), step(v.y1, v.y2));
+    let mut p = (v.x1, v.y1);
+    loop {
+        board[p] = board[p].saturating_add(1);
+        if p == (v.x2, v.y2) {
+            break;
         }
+        p = (p.0.wrapping_add_signed(dx), p.1.wrapping_add_signed(dy));
     }
 }
 
-impl Index<(usize, usize)> for Board {
-    type Output = u8;
-
-    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
-        &self.data[x][y]
+fn count_overlaps<'a>(vents: impl Iterator<Item = &'a Vent> + Clone) -> Result<usize, PuzzleError> {
+    let width = vents.clone().map(|v| v.x1.max(v.x2) + 1).max().unwrap_or(0);
+    let height = vents.clone().map(|v| v.y1.max(v.y2) + 1).max().unwrap_or(0);
+    let mut board = Grid::new(width, height, 0u8)?;
+    for v in vents {
+        draw_line(&mut board, v);
     }
+    Ok(board.values().filter(|n| **n > 1).count())
 }
 
-impl IndexMut<(usize, usize)> for Board {
-    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
-        &mut self.data[x][y]
-    }
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Vent {
+    pub x1: usize,
+    pub y1: usize,
+    pub x2: usize,
+    pub y2: usize,
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> usize {
-    let mut board = Board::default();
-    for res in reader.lines() {
-        let line = res.unwrap();
-        let parts: Vec<_> = line.split(" -> ").collect();
-        match parts.as_slice() {
-            [p1, p2] => {
-                let d1: Vec<_> = p1.split(",").map(|n| n.parse::<usize>().unwrap()).collect();
-                let d2: Vec<_> = p2.split(",").map(|n| n.parse::<usize>().unwrap()).collect();
-                match (d1.as_slice(), d2.as_slice()) {
-                    ([x1, y1], [x2, y2]) => {
-                        if *x1 == *x2 || *y1 == *y2 {
-                            board.draw_line(*x1, *y1, *x2, *y2);
-                        }
-                    }
-                    _ => panic!(),
-                }
+/// The largest coordinate a vent may have, keeping the dense ocean floor small enough to hold.
+pub const MAX_COORDINATE: usize = 4095;
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Vent>, PuzzleError> {
+    fn parse_coordinate(span: Span) -> Result<usize, PuzzleError> {
+        match span.parse("a coordinate `x,y`")? {
+            n if n > MAX_COORDINATE => {
+                Err(span.error(format!("a coordinate no greater than {}", MAX_COORDINATE)))
             }
-            _ => panic!(),
+            n => Ok(n),
         }
     }
 
-    board
-        .data
-        .iter()
-        .flat_map(|r| r.iter().copied())
-        .filter(|n| *n > 1)
-        .count()
-}
+    fn parse_point(span: Span) -> Result<(usize, usize), PuzzleError> {
+        let (x, y) = span.split_once(",", "a coordinate `x,y`")?;
+        Ok((parse_coordinate(x)?, parse_coordinate(y)?))
+    }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> usize {
-    let mut board = Board::default();
-    for res in reader.lines() {
-        let line = res.unwrap();
-        let parts: Vec<_> = line.split(" -> ").collect();
-        match parts.as_slice() {
-            [p1, p2] => {
-                let d1: Vec<_> = p1.split(",").map(|n| n.parse::<usize>().unwrap()).collect();
-                let d2: Vec<_> = p2.split(",").map(|n| n.parse::<usize>().unwrap()).collect();
-                match (d1.as_slice(), d2.as_slice()) {
-                    ([x1, y1], [x2, y2]) => board.draw_line(*x1, *y1, *x2, *y2),
-                    _ => panic!(),
-                }
+    lines(reader)
+        .map(|line| {
+            let line = line?;
+            let (p1, p2) = line
+                .span()
+                .split_once(" -> ", "a line segment `x1,y1 -> x2,y2`")?;
+            let (x1, y1) = parse_point(p1)?;
+            let (x2, y2) = parse_point(p2)?;
+            if x1 != x2 && y1 != y2 && x1.abs_diff(x2) != y1.abs_diff(y2) {
+                return Err(line
+                    .span()
+                    .error("a horizontal, vertical or diagonal line segment"));
             }
-            _ => panic!(),
-        }
-    }
+            Ok(Vent { x1, y1, x2, y2 })
+        })
+        .collect()
+}
+
+pub fn part1(vents: &[Vent]) -> Result<usize, PuzzleError> {
+    count_overlaps(vents.iter().filter(|v| v.x1 == v.x2 || v.y1 == v.y2))
+}
 
-    board
-        .data
-        .iter()
-        .flat_map(|r| r.iter().copied())
-        .filter(|n| *n > 1)
-        .count()
+pub fn part2(vents: &[Vent]) -> Result<usize, PuzzleError> {
+    count_overlaps(vents.iter())
 }
@@ -1,49 +1,73 @@
+use crate::error::PuzzleError;
+use crate::params::params;
+use crate::parse::lines;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> u32 {
-    let line = reader.lines().next().unwrap().unwrap();
+pub fn parse<R: BufRead>(reader: R) -> Result<[u64; 9], PuzzleError> {
+    const EXPECTED: &str = "a timer between 0 and 8";
+    let line = lines(reader).expect("a list of timers")?;
     let mut counts = [0; 9];
-    line.split(',')
-        .map(|n| n.parse::<usize>().unwrap())
-        .for_each(|n| {
-            counts[n] += 1;
-        });
-
-    for _ in 0..80 {
-        let mut new_counts = [0; 9];
-        for i in 0..8 {
-            new_counts[i] += counts[i + 1];
+    for n in line.span().split(",") {
+        match n.parse::<usize>(EXPECTED)? {
+            n if n < counts.len() => counts[n] += 1,
+            _ => return Err(n.error(EXPECTED)),
         }
-        new_counts[6] += counts[0];
-        new_counts[8] += counts[0];
+    }
+    Ok(counts)
+}
 
-        std::mem::swap(&mut new_counts, &mut counts);
+params! {
+    pub struct Params {
+        /// Days simulated for part 1.
+        pub part1_days: usize = 80,
+        /// Days simulated for part 2.
+        pub part2_days: usize = 256,
     }
+}
 
-    counts.iter().sum()
+fn too_many_fish() -> PuzzleError {
+    PuzzleError::no_solution("there are more fish than a u64 can count")
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u64 {
-    let line = reader.lines().next().unwrap().unwrap();
-    let mut counts = [0_u64; 9];
-    line.split(',')
-        .map(|n| n.parse::<usize>().unwrap())
-        .for_each(|n| {
-            counts[n] += 1;
-        });
+/// Advances the fish, counted by timer value, by one day. Fails, leaving the counts as they
+/// were, if there would be too many fish to count.
+pub fn step(counts: &mut [u64; 9]) -> Result<(), PuzzleError> {
+    let mut new_counts = [0; 9];
+    for i in 0..8 {
+        new_counts[i] += counts[i + 1];
+    }
+    new_counts[6] = new_counts[6]
+        .checked_add(counts[0])
+        .ok_or_else(too_many_fish)?;
+    new_counts[8] += counts[0];
 
-    for _ in 0..256 {
-        let mut new_counts = [0; 9];
-        for i in 0..8 {
-            new_counts[i] += counts[i + 1];
-        }
-        new_counts[6] += counts[0];
-        new_counts[8] += counts[0];
+    *counts = new_counts;
+    Ok(())
+}
 
-        std::mem::swap(&mut new_counts, &mut counts);
+fn simulate(counts: &[u64; 9], days: usize) -> Result<u64, PuzzleError> {
+    let mut counts = *counts;
+    for _ in 0..days {
+        step(&mut counts)?;
     }
+    counts
+        .iter()
+        .try_fold(0u64, |sum, &n| sum.checked_add(n))
+        .ok_or_else(too_many_fish)
+}
+
+pub fn part1(counts: &[u64; 9]) -> Result<u64, PuzzleError> {
+    part1_with(counts, &Params::default())
+}
+
+pub fn part1_with(counts: &[u64; 9], params: &Params) -> Result<u64, PuzzleError> {
+    simulate(counts, params.part1_days)
+}
+
+pub fn part2(counts: &[u64; 9]) -> Result<u64, PuzzleError> {
+    part2_with(counts, &Params::default())
+}
 
-    counts.iter().sum()
+pub fn part2_with(counts: &[u64; 9], params: &Params) -> Result<u64, PuzzleError> {
+    simulate(counts, params.part2_days)
 }
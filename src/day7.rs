@@ -1,39 +1,41 @@
+use crate::error::PuzzleError;
+use crate::parse::lines;
 use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> i32 {
-    let positions: Vec<i32> = reader
-        .lines()
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse::<i32>().unwrap())
-        .collect();
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<i32>, PuzzleError> {
+    lines(reader)
+        .expect("a list of positions")?
+        .span()
+        .split(",")
+        .map(|n| n.parse("a position"))
+        .collect()
+}
 
-    (positions.iter().copied().min().unwrap()..positions.iter().copied().max().unwrap())
-        .map(|p| positions.iter().copied().map(|s| i32::abs(s - p)).sum())
-        .min()
-        .unwrap()
+pub fn part1(positions: &[i32]) -> Result<i64, PuzzleError> {
+    cheapest(positions, Some)
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> i32 {
-    let positions: Vec<i32> = reader
-        .lines()
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse::<i32>().unwrap())
-        .collect();
+pub fn part2(positions: &[i32]) -> Result<i64, PuzzleError> {
+    cheapest(positions, dist_to_fuel)
+}
 
-    (positions.iter().copied().min().unwrap()..positions.iter().copied().max().unwrap())
-        .map(|p| positions.iter().copied().map(|s| dist_to_fuel(s - p)).sum())
+/// The least fuel for every crab to reach one position, where `fuel` is the cost of moving a
+/// crab a distance, or `None` if that cost does not fit in an `i64`.
+fn cheapest(positions: &[i32], fuel: fn(i64) -> Option<i64>) -> Result<i64, PuzzleError> {
+    let total = |p: i64| {
+        positions.iter().try_fold(0i64, |total, &s| {
+            total.checked_add(fuel((s as i64 - p).abs())?)
+        })
+    };
+    let (Some(&min), Some(&max)) = (positions.iter().min(), positions.iter().max()) else {
+        return Err(PuzzleError::no_solution("there are no crabs"));
+    };
+    (min..=max)
+        .filter_map(|p| total(p as i64))
         .min()
-        .unwrap()
+        .ok_or_else(|| PuzzleError::no_solution("the fuel needed does not fit in 64 bits"))
 }
 
-fn dist_to_fuel(dist: i32) -> i32 {
-    (1..=i32::abs(dist)).sum()
+fn dist_to_fuel(dist: i64) -> Option<i64> {
+    dist.checked_mul(dist + 1).map(|twice| twice / 2)
 }
@@ -1,9 +1,11 @@
+use crate::error::PuzzleError;
+use crate::parse::{lines, Span};
 use bitflags::bitflags;
 use std::collections::HashMap;
 use std::io::BufRead;
 
 bitflags! {
-    struct Segments: u8 {
+    pub struct Segments: u8 {
         const A = 0b0000_0001;
         const B = 0b0000_0010;
         const C = 0b0000_0100;
@@ -28,144 +30,150 @@ enum Digit {
     Nine,
 }
 
-fn parse_line(text: &str) -> Vec<Segments> {
-    text.split(' ')
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub line: usize,
+    pub given: Vec<Segments>,
+    pub numbers: Vec<Segments>,
+}
+
+fn parse_line(span: Span, count: usize) -> Result<Vec<Segments>, PuzzleError> {
+    let patterns = span
+        .split_whitespace()
         .map(|p| {
             p.chars()
-                .map(|c| match c {
-                    'a' => Segments::A,
-                    'b' => Segments::B,
-                    'c' => Segments::C,
-                    'd' => Segments::D,
-                    'e' => Segments::E,
-                    'f' => Segments::F,
-                    'g' => Segments::G,
-                    _ => panic!(),
+                .map(|(c, span)| match c {
+                    'a' => Ok(Segments::A),
+                    'b' => Ok(Segments::B),
+                    'c' => Ok(Segments::C),
+                    'd' => Ok(Segments::D),
+                    'e' => Ok(Segments::E),
+                    'f' => Ok(Segments::F),
+                    'g' => Ok(Segments::G),
+                    _ => Err(span.error("a segment between `a` and `g`")),
                 })
-                .fold(Segments::empty(), |s1, s2| s1 | s2)
+                .collect::<Result<Segments, _>>()
         })
-        .filter(|s| !s.is_empty())
-        .collect()
+        .collect::<Result<Vec<_>, _>>()?;
+    if patterns.len() != count {
+        return Err(span.error(format!("{} signal patterns", count)));
+    }
+    Ok(patterns)
 }
 
-#[allow(dead_code)]
-pub fn part1<R: BufRead>(reader: R) -> usize {
-    reader
-        .lines()
-        .flat_map(|result| {
-            let line = result.unwrap();
-            let parts = line.split('|').collect::<Vec<_>>();
-            let numbers_text = match parts.as_slice() {
-                [_, numbers] => numbers,
-                _ => panic!(),
-            };
-            parse_line(numbers_text).into_iter()
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Entry>, PuzzleError> {
+    lines(reader)
+        .map(|line| {
+            let line = line?;
+            let (given_text, numbers_text) = line
+                .span()
+                .split_once("|", "signal patterns and output values separated by `|`")?;
+            Ok(Entry {
+                line: line.number,
+                given: parse_line(given_text, 10)?,
+                numbers: parse_line(numbers_text, 4)?,
+            })
         })
+        .collect()
+}
+
+pub fn part1(entries: &[Entry]) -> Result<usize, PuzzleError> {
+    Ok(entries
+        .iter()
+        .flat_map(|entry| entry.numbers.iter())
         .filter(|segments| [2, 4, 3, 7].contains(&segments.bits.count_ones()))
-        .count()
+        .count())
 }
 
-#[allow(dead_code)]
-pub fn part2<R: BufRead>(reader: R) -> u32 {
-    let mut total = 0;
-    for result in reader.lines() {
-        let line = result.unwrap();
-        let parts = line.split('|').collect::<Vec<_>>();
-        let (given_text, numbers_text) = match parts.as_slice() {
-            [given, numbers] => (given, numbers),
-            _ => panic!(),
-        };
-        let given = parse_line(given_text);
-        let numbers = parse_line(numbers_text);
+pub fn part2(entries: &[Entry]) -> Result<u64, PuzzleError> {
+    // Each entry adds at most 9999, so a u64 total cannot overflow.
+    let mut total = 0u64;
+    for entry in entries {
+        total += u64::from(decode(&entry.given, &entry.numbers).ok_or_else(|| {
+            PuzzleError::no_solution(format!(
+                "the signal patterns on line {} do not match a seven-segment display",
+                entry.line
+            ))
+        })?);
+    }
 
-        let mut segments_to_given: HashMap<u8, Vec<Segments>> = HashMap::new();
-        for given_segments in given.into_iter() {
-            segments_to_given
-                .entry(given_segments.bits.count_ones() as u8)
-                .or_default()
-                .push(given_segments);
-        }
+    Ok(total)
+}
 
-        let one_segments = segments_to_given.get(&2).unwrap()[0];
-        let seven_segments = segments_to_given.get(&3).unwrap()[0];
-        let four_segments = segments_to_given.get(&4).unwrap()[0];
-        let eight_segments = segments_to_given.get(&7).unwrap()[0];
+fn decode(given: &[Segments], numbers: &[Segments]) -> Option<u32> {
+    let mut segments_to_given: HashMap<u8, Vec<Segments>> = HashMap::new();
+    for given_segments in given.iter().copied() {
+        segments_to_given
+            .entry(given_segments.bits.count_ones() as u8)
+            .or_default()
+            .push(given_segments);
+    }
 
-        let zero_six_nine_segments = segments_to_given.get(&6).unwrap();
-        let nine_segments = zero_six_nine_segments
-            .iter()
-            .filter(|segments| segments.intersection(four_segments).bits.count_ones() == 4)
-            .next()
-            .copied()
-            .unwrap();
-        let zero_segments = zero_six_nine_segments
-            .iter()
-            .filter(|segments| **segments != nine_segments)
-            .filter(|segments| segments.intersection(one_segments).bits.count_ones() == 2)
-            .next()
-            .copied()
-            .unwrap();
-        let six_segments = zero_six_nine_segments
-            .iter()
-            .filter(|segments| **segments != nine_segments)
-            .filter(|segments| **segments != zero_segments)
-            .next()
-            .copied()
-            .unwrap();
+    let one_segments = *segments_to_given.get(&2)?.first()?;
+    let seven_segments = *segments_to_given.get(&3)?.first()?;
+    let four_segments = *segments_to_given.get(&4)?.first()?;
+    let eight_segments = *segments_to_given.get(&7)?.first()?;
 
-        let two_three_five_segments = segments_to_given.get(&5).unwrap();
-        let three_segments = two_three_five_segments
-            .iter()
-            .filter(|segments| segments.intersection(one_segments).bits.count_ones() == 2)
-            .next()
-            .copied()
-            .unwrap();
-        let five_segments = two_three_five_segments
-            .iter()
-            .filter(|segments| **segments != three_segments)
-            .filter(|segments| segments.difference(six_segments).bits.count_ones() == 0)
-            .next()
-            .copied()
-            .unwrap();
-        let two_segments = two_three_five_segments
-            .iter()
-            .filter(|segments| **segments != three_segments)
-            .filter(|segments| **segments != five_segments)
-            .next()
-            .copied()
-            .unwrap();
+    let zero_six_nine_segments = segments_to_given.get(&6)?;
+    let nine_segments = zero_six_nine_segments
+        .iter()
+        .find(|segments| segments.intersection(four_segments).bits.count_ones() == 4)
+        .copied()?;
+    let zero_segments = zero_six_nine_segments
+        .iter()
+        .filter(|segments| **segments != nine_segments)
+        .find(|segments| segments.intersection(one_segments).bits.count_ones() == 2)
+        .copied()?;
+    let six_segments = zero_six_nine_segments
+        .iter()
+        .filter(|segments| **segments != nine_segments)
+        .find(|segments| **segments != zero_segments)
+        .copied()?;
 
-        let mut lookup = HashMap::new();
-        lookup.insert(zero_segments, Digit::Zero);
-        lookup.insert(one_segments, Digit::One);
-        lookup.insert(two_segments, Digit::Two);
-        lookup.insert(three_segments, Digit::Three);
-        lookup.insert(four_segments, Digit::Four);
-        lookup.insert(five_segments, Digit::Five);
-        lookup.insert(six_segments, Digit::Six);
-        lookup.insert(seven_segments, Digit::Seven);
-        lookup.insert(eight_segments, Digit::Eight);
-        lookup.insert(nine_segments, Digit::Nine);
+    let two_three_five_segments = segments_to_given.get(&5)?;
+    let three_segments = two_three_five_segments
+        .iter()
+        .find(|segments| segments.intersection(one_segments).bits.count_ones() == 2)
+        .copied()?;
+    let five_segments = two_three_five_segments
+        .iter()
+        .filter(|segments| **segments != three_segments)
+        .find(|segments| segments.difference(six_segments).bits.count_ones() == 0)
+        .copied()?;
+    let two_segments = two_three_five_segments
+        .iter()
+        .filter(|segments| **segments != three_segments)
+        .find(|segments| **segments != five_segments)
+        .copied()?;
 
-        let mut number = 0;
-        for n in numbers {
-            number *= 10;
-            match lookup.get(&n).unwrap() {
-                Digit::Zero => {}
-                Digit::One => number += 1,
-                Digit::Two => number += 2,
-                Digit::Three => number += 3,
-                Digit::Four => number += 4,
-                Digit::Five => number += 5,
-                Digit::Six => number += 6,
-                Digit::Seven => number += 7,
-                Digit::Eight => number += 8,
-                Digit::Nine => number += 9,
-            }
-        }
+    let mut lookup = HashMap::new();
+    lookup.insert(zero_segments, Digit::Zero);
+    lookup.insert(one_segments, Digit::One);
+    lookup.insert(two_segments, Digit::Two);
+    lookup.insert(three_segments, Digit::Three);
+    lookup.insert(four_segments, Digit::Four);
+    lookup.insert(five_segments, Digit::Five);
+    lookup.insert(six_segments, Digit::Six);
+    lookup.insert(seven_segments, Digit::Seven);
+    lookup.insert(eight_segments, Digit::Eight);
+    lookup.insert(nine_segments, Digit::Nine);
 
-        total += number;
+    let mut number = 0;
+    for n in numbers {
+        number *= 10;
+        match lookup.get(n)? {
+            Digit::Zero => {}
+            Digit::One => number += 1,
+            Digit::Two => number += 2,
+            Digit::Three => number += 3,
+            Digit::Four => number += 4,
+            Digit::Five => number += 5,
+            Digit::Six => number += 6,
+            Digit::Seven => number += 7,
+            Digit::Eight => number += 8,
+            Digit::Nine => number += 9,
+        }
     }
 
-    total
+    Some(number)
 }
@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum PuzzleError {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        expected: String,
+        found: String,
+    },
+    UnexpectedEof {
+        line: usize,
+        expected: String,
+    },
+    NoSolution(String),
+}
+
+impl PuzzleError {
+    pub fn no_solution(reason: impl Into<String>) -> Self {
+        PuzzleError::NoSolution(reason.into())
+    }
+}
+
+/// `io::Error` is not `Clone`, so a copy of an I/O error keeps only its kind and message.
+impl Clone for PuzzleError {
+    fn clone(&self) -> Self {
+        match self {
+            PuzzleError::Io(e) => PuzzleError::Io(io::Error::new(e.kind(), e.to_string())),
+            PuzzleError::Parse {
+                line,
+                column,
+                expected,
+                found,
+            } => PuzzleError::Parse {
+                line: *line,
+                column: *column,
+                expected: expected.clone(),
+                found: found.clone(),
+            },
+            PuzzleError::UnexpectedEof { line, expected } => PuzzleError::UnexpectedEof {
+                line: *line,
+                expected: expected.clone(),
+            },
+            PuzzleError::NoSolution(reason) => PuzzleError::NoSolution(reason.clone()),
+        }
+    }
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::Io(e) => write!(f, "failed to read input: {}", e),
+            PuzzleError::Parse {
+                line,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: expected {}, found {:?}",
+                line, column, expected, found
+            ),
+            PuzzleError::UnexpectedEof { line, expected } => write!(
+                f,
+                "line {}: expected {}, found end of input",
+                line, expected
+            ),
+            PuzzleError::NoSolution(reason) => write!(f, "no solution: {}", reason),
+        }
+    }
+}
+
+impl Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PuzzleError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PuzzleError {
+    fn from(e: io::Error) -> Self {
+        PuzzleError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown {
+        key: String,
+        known: Vec<&'static str>,
+    },
+    Invalid {
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown { key, known } if known.is_empty() => {
+                write!(f, "unknown parameter `{}`: this day has no parameters", key)
+            }
+            ParamError::Unknown { key, known } => write!(
+                f,
+                "unknown parameter `{}`: expected one of {}",
+                key,
+                known.join(", ")
+            ),
+            ParamError::Invalid {
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value {:?} for `{}`: expected {}",
+                value, key, expected
+            ),
+        }
+    }
+}
+
+impl Error for ParamError {}
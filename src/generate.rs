@@ -0,0 +1,602 @@
+//! Random puzzle inputs for stress testing and fuzzing.
+//!
+//! Every generator is deterministic for a given seed and produces an input that the day's
+//! parser accepts. `size` scales the input roughly linearly: it is the number of lines for
+//! line-based days, and the side length or element count for the others, as listed in
+//! [`describe_size`].
+
+use crate::grid::{Grid, Point};
+use std::fmt::Write;
+
+/// A small, seedable SplitMix64 generator, so inputs are reproducible without extra crates.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..n`, or 0 if `n` is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        self.next_u64() % n
+    }
+
+    /// A value in `lo..=hi`.
+    pub fn between(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + self.below((hi - lo) as u64 + 1) as i64
+    }
+
+    /// True with probability `percent` / 100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+type Generator = fn(&mut Rng, usize) -> String;
+
+static GENERATORS: &[(u8, usize, &str, Generator)] = &[
+    (1, 2000, "depths", day1),
+    (2, 1000, "commands", day2),
+    (3, 1000, "numbers", day3),
+    (4, 100, "boards", day4),
+    (5, 500, "vent lines", day5),
+    (6, 300, "fish", day6),
+    (7, 1000, "crabs", day7),
+    (8, 200, "displays", day8),
+    (9, 100, "heightmap side length", day9),
+    (10, 100, "lines", day10),
+    (11, 10, "grid side length", day11),
+    (12, 6, "small caves", day12),
+    (13, 800, "dots", day13),
+    (14, 10, "distinct elements", day14),
+    (15, 100, "cave side length", day15),
+    (16, 60, "packets", day16),
+    (17, 100, "target distance", day17),
+];
+
+/// The default size for `day`, close to the size of the real puzzle input.
+pub fn default_size(day: u8) -> Option<usize> {
+    GENERATORS
+        .iter()
+        .find(|(n, ..)| *n == day)
+        .map(|(_, size, ..)| *size)
+}
+
+/// What `size` counts for `day`.
+pub fn describe_size(day: u8) -> Option<&'static str> {
+    GENERATORS
+        .iter()
+        .find(|(n, ..)| *n == day)
+        .map(|(_, _, what, _)| *what)
+}
+
+/// A random input for `day`, or `None` if there is no generator for it.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let (_, _, _, generator) = GENERATORS.iter().find(|(n, ..)| *n == day)?;
+    Some(generator(&mut Rng::new(seed), size.max(1)))
+}
+
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    let mut depth = rng.between(100, 200);
+    for _ in 0..size {
+        writeln!(out, "{}", depth).unwrap();
+        depth = (depth + rng.between(-10, 30)).clamp(0, u16::MAX as i64);
+    }
+    out
+}
+
+fn day2(rng: &mut Rng, size: usize) -> String {
+    // The submarine never surfaces past zero depth, which the simple model cannot represent.
+    let mut out = String::new();
+    let mut depth = 0;
+    for _ in 0..size {
+        let distance = rng.between(1, 9);
+        match rng.below(3) {
+            0 => writeln!(out, "forward {}", distance).unwrap(),
+            1 => {
+                depth += distance;
+                writeln!(out, "down {}", distance).unwrap();
+            }
+            _ => {
+                let distance = distance.min(depth);
+                if distance == 0 {
+                    writeln!(out, "down 1").unwrap();
+                    depth += 1;
+                } else {
+                    depth -= distance;
+                    writeln!(out, "up {}", distance).unwrap();
+                }
+            }
+        }
+    }
+    out
+}
+
+fn day3(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        writeln!(out, "{:012b}", rng.below(1 << 12)).unwrap();
+    }
+    out
+}
+
+fn day4(rng: &mut Rng, size: usize) -> String {
+    let mut nums: Vec<u8> = (0..100).collect();
+    rng.shuffle(&mut nums);
+    let drawn: Vec<String> = nums.iter().map(u8::to_string).collect();
+    let mut out = drawn.join(",");
+    out.push('\n');
+    for _ in 0..size {
+        rng.shuffle(&mut nums);
+        out.push('\n');
+        for row in nums[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+            writeln!(out, "{}", row.join(" ")).unwrap();
+        }
+    }
+    out
+}
+
+fn day5(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let (x1, y1) = (rng.between(0, 989), rng.between(0, 989));
+        let length = rng.between(1, 300);
+        let (dx, dy) = *rng.choose(&[
+            (1, 0),
+            (0, 1),
+            (1, 1),
+            (1, -1),
+            (-1, 0),
+            (0, -1),
+            (-1, 1),
+            (-1, -1),
+        ]);
+        let x2 = (x1 + dx * length).clamp(0, 989);
+        let y2 = (y1 + dy * length).clamp(0, 989);
+        // Clamping can shorten one axis of a diagonal, so shrink both to the shorter one.
+        let steps = if dx != 0 && dy != 0 {
+            (x2 - x1).abs().min((y2 - y1).abs())
+        } else {
+            (x2 - x1).abs().max((y2 - y1).abs())
+        };
+        writeln!(
+            out,
+            "{},{} -> {},{}",
+            x1,
+            y1,
+            x1 + dx * steps,
+            y1 + dy * steps
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn day6(rng: &mut Rng, size: usize) -> String {
+    let timers: Vec<String> = (0..size).map(|_| rng.between(1, 5).to_string()).collect();
+    timers.join(",") + "\n"
+}
+
+fn day7(rng: &mut Rng, size: usize) -> String {
+    let positions: Vec<String> = (0..size)
+        .map(|_| {
+            // Crabs bunch up towards low positions, like the real input.
+            let a = rng.below(2000);
+            let b = rng.below(2000);
+            a.min(b).to_string()
+        })
+        .collect();
+    positions.join(",") + "\n"
+}
+
+const SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// The segments of `digit` rewired through `wiring`, in random order.
+fn scramble(rng: &mut Rng, wiring: &[char], digit: usize) -> String {
+    let mut wires: Vec<char> = SEGMENTS[digit]
+        .chars()
+        .map(|c| wiring[c as usize - 'a' as usize])
+        .collect();
+    rng.shuffle(&mut wires);
+    wires.into_iter().collect()
+}
+
+fn day8(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut wiring: Vec<char> = "abcdefg".chars().collect();
+        rng.shuffle(&mut wiring);
+        let mut patterns: Vec<String> = (0..10).map(|d| scramble(rng, &wiring, d)).collect();
+        rng.shuffle(&mut patterns);
+        let outputs: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = rng.below(10) as usize;
+                scramble(rng, &wiring, digit)
+            })
+            .collect();
+        writeln!(out, "{} | {}", patterns.join(" "), outputs.join(" ")).unwrap();
+    }
+    out
+}
+
+fn digit_grid(
+    rng: &mut Rng,
+    width: usize,
+    height: usize,
+    digit: impl Fn(&mut Rng) -> u64,
+) -> String {
+    let mut out = String::new();
+    for _ in 0..height {
+        for _ in 0..width {
+            out.push(char::from_digit(digit(rng) as u32, 10).unwrap());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn day9(rng: &mut Rng, size: usize) -> String {
+    // Mostly low ground with ridges of nines, so the heightmap splits into basins.
+    let nines = rng.between(15, 30) as u64;
+    digit_grid(rng, size, size, |rng| {
+        if rng.chance(nines) {
+            9
+        } else {
+            rng.below(9)
+        }
+    })
+}
+
+fn day10(rng: &mut Rng, size: usize) -> String {
+    const OPEN: [char; 4] = ['(', '[', '{', '<'];
+    const CLOSE: [char; 4] = [')', ']', '}', '>'];
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut stack = Vec::new();
+        let length = rng.between(20, 110);
+        // About half of the lines get a single wrong closing bracket.
+        let corrupt_at = if rng.chance(50) {
+            Some(rng.between(1, length))
+        } else {
+            None
+        };
+        for i in 0..length {
+            if Some(i) == corrupt_at && !stack.is_empty() {
+                let expected: usize = *stack.last().unwrap();
+                out.push(CLOSE[(expected + 1 + rng.below(3) as usize) % 4]);
+                break;
+            }
+            // Real lines never leave more than a couple dozen brackets open.
+            if stack.is_empty() || (stack.len() < 20 && rng.chance(55)) {
+                let bracket = rng.below(4) as usize;
+                stack.push(bracket);
+                out.push(OPEN[bracket]);
+            } else {
+                out.push(CLOSE[stack.pop().unwrap()]);
+            }
+        }
+        if stack.is_empty() {
+            // Keep every line incomplete or corrupted, as in the puzzle.
+            out.push(OPEN[rng.below(4) as usize]);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Whether every octopus flashes in the same step within `steps` steps.
+fn synchronizes(levels: &str, steps: usize) -> bool {
+    let mut grid = match Grid::parse_digits(levels.as_bytes()) {
+        Ok(grid) => grid,
+        Err(_) => return false,
+    };
+    for _ in 0..steps {
+        let mut flashing: Vec<Point> = Vec::new();
+        for (p, level) in grid.iter() {
+            if *level == 9 {
+                flashing.push(p);
+            }
+        }
+        grid = grid.map(|level| level + 1);
+        while let Some(p) = flashing.pop() {
+            let neighbors: Vec<Point> = grid.neighbors8(p).collect();
+            for n in neighbors {
+                grid[n] += 1;
+                if grid[n] == 10 {
+                    flashing.push(n);
+                }
+            }
+        }
+        if grid.values().all(|level| *level > 9) {
+            return true;
+        }
+        grid = grid.map(|level| if *level > 9 { 0 } else { *level });
+    }
+    false
+}
+
+fn day11(rng: &mut Rng, size: usize) -> String {
+    // Random grids usually synchronize within a few hundred steps, but checking takes long on
+    // big grids, so those and unlucky small ones get a grid that is known to synchronize.
+    if size <= 20 {
+        for _ in 0..10 {
+            let levels = digit_grid(rng, size, size, |rng| rng.below(10));
+            if synchronizes(&levels, 1000) {
+                return levels;
+            }
+        }
+    }
+    // One level everywhere except some cells a level lower, each next to a cell at the higher
+    // level. When the higher cells flash, they push their lower neighbours to flash with them.
+    let level = rng.between(1, 9) as u32;
+    let mut lowered = Grid::from_fn(size, size, |_| rng.chance(40))
+        .expect("the grid is no bigger than the text it is written to");
+    for p in lowered.points().collect::<Vec<_>>() {
+        if lowered[p] && lowered.neighbors8(p).all(|n| lowered[n]) {
+            lowered[p] = false;
+        }
+    }
+    let mut out = String::new();
+    for row in lowered.rows() {
+        for &low in row {
+            out.push(char::from_digit(level - low as u32, 10).unwrap());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn day12(rng: &mut Rng, size: usize) -> String {
+    // Big caves are never linked to each other, otherwise there would be infinitely many paths.
+    // Names have two letters like the puzzle's, or more when two cannot tell all caves apart.
+    let mut length = 2;
+    while 26f64.powi(length) < 2.0 * size as f64 {
+        length += 1;
+    }
+    let name = |rng: &mut Rng, upper: bool, taken: &[String]| loop {
+        let letters = if upper { b'A' } else { b'a' };
+        let name: String = (0..length)
+            .map(|_| (letters + rng.below(26) as u8) as char)
+            .collect();
+        if !taken.contains(&name) && name != "start" && name != "end" {
+            return name;
+        }
+    };
+    let mut small: Vec<String> = Vec::new();
+    for _ in 0..size {
+        let cave = name(rng, false, &small);
+        small.push(cave);
+    }
+    let mut big: Vec<String> = Vec::new();
+    for _ in 0..size.div_ceil(3) {
+        let cave = name(rng, true, &big);
+        big.push(cave);
+    }
+
+    let mut edges = Vec::new();
+    let mut link = |a: &str, b: &str| {
+        if a != b
+            && !edges.contains(&(a.to_string(), b.to_string()))
+            && !edges.contains(&(b.to_string(), a.to_string()))
+        {
+            edges.push((a.to_string(), b.to_string()));
+        }
+    };
+    for cave in small.iter() {
+        let other = rng.choose(&big).clone();
+        link(cave, &other);
+    }
+    for _ in 0..size {
+        let a = rng.choose(&small).clone();
+        let b = rng.choose(&small).clone();
+        link(&a, &b);
+    }
+    for end in ["start", "end"] {
+        for _ in 0..2 {
+            let cave = if rng.chance(50) {
+                rng.choose(&small).clone()
+            } else {
+                rng.choose(&big).clone()
+            };
+            link(end, &cave);
+        }
+    }
+
+    rng.shuffle(&mut edges);
+    let mut out = String::new();
+    for (a, b) in edges {
+        writeln!(out, "{}-{}", a, b).unwrap();
+    }
+    out
+}
+
+fn day13(rng: &mut Rng, size: usize) -> String {
+    // Unfold a small final sheet: each fold halves a side of length 2n + 1 at n. Dots start on
+    // the final sheet and are mirrored out through random folds, so none lies on a fold line.
+    let (width, height) = (rng.between(5, 40), rng.between(5, 8));
+    let mut folds = Vec::new();
+    let (mut x_fold, mut y_fold) = (width, height);
+    for _ in 0..rng.between(2, 6) {
+        if rng.chance(50) {
+            folds.push(('x', x_fold));
+            x_fold = 2 * x_fold + 1;
+        } else {
+            folds.push(('y', y_fold));
+            y_fold = 2 * y_fold + 1;
+        }
+    }
+
+    let mut out = String::new();
+    for _ in 0..size {
+        let (mut x, mut y) = (rng.between(0, width - 1), rng.between(0, height - 1));
+        for (axis, val) in folds.iter().copied() {
+            if rng.chance(50) {
+                match axis {
+                    'x' => x = 2 * val - x,
+                    _ => y = 2 * val - y,
+                }
+            }
+        }
+        writeln!(out, "{},{}", x, y).unwrap();
+    }
+    out.push('\n');
+    for (axis, val) in folds.iter().rev() {
+        writeln!(out, "fold along {}={}", axis, val).unwrap();
+    }
+    out
+}
+
+fn day14(rng: &mut Rng, size: usize) -> String {
+    let elements: Vec<char> = (b'A'..=b'Z').take(size.min(26)).map(char::from).collect();
+    let template: String = (0..rng.between(4, 20))
+        .map(|_| *rng.choose(&elements))
+        .collect();
+    let mut out = template + "\n\n";
+    for a in elements.iter() {
+        for b in elements.iter() {
+            writeln!(out, "{}{} -> {}", a, b, rng.choose(&elements)).unwrap();
+        }
+    }
+    out
+}
+
+fn day15(rng: &mut Rng, size: usize) -> String {
+    digit_grid(rng, size, size, |rng| rng.between(1, 9) as u64)
+}
+
+enum Packet {
+    Literal(u64),
+    Operator(u8, Vec<Packet>),
+}
+
+impl Packet {
+    fn value(&self) -> Option<u64> {
+        match self {
+            Packet::Literal(n) => Some(*n),
+            Packet::Operator(type_id, children) => {
+                let mut values = children.iter().map(Packet::value);
+                match type_id {
+                    0 => values.try_fold(0u64, |acc, v| acc.checked_add(v?)),
+                    1 => values.try_fold(1u64, |acc, v| acc.checked_mul(v?)),
+                    2 => values.try_fold(u64::MAX, |acc, v| Some(acc.min(v?))),
+                    3 => values.try_fold(0, |acc, v| Some(acc.max(v?))),
+                    _ => {
+                        let (a, b) = (values.next()??, values.next()??);
+                        Some(match type_id {
+                            5 => (a > b) as u64,
+                            6 => (a < b) as u64,
+                            _ => (a == b) as u64,
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    /// A random packet tree of about `budget` packets whose value fits in a u64.
+    fn random(rng: &mut Rng, budget: usize) -> Packet {
+        if budget <= 1 || rng.chance(25) {
+            let limit = if rng.chance(20) { 1 << 40 } else { 4096 };
+            return Packet::Literal(rng.below(limit));
+        }
+        let type_id = *rng.choose(&[0, 1, 2, 3, 5, 6, 7]);
+        let count = match type_id {
+            5..=7 => 2,
+            _ => rng.between(1, 4) as usize,
+        };
+        let children = (0..count)
+            .map(|_| Packet::random(rng, (budget - 1) / count))
+            .collect();
+        let packet = Packet::Operator(type_id, children);
+        match packet.value() {
+            Some(_) => packet,
+            None => Packet::Literal(rng.below(4096)),
+        }
+    }
+
+    fn encode(&self, rng: &mut Rng, bits: &mut Vec<bool>) {
+        let push = |bits: &mut Vec<bool>, n: u64, width: usize| {
+            bits.extend((0..width).rev().map(|i| n >> i & 1 == 1));
+        };
+        push(bits, rng.below(8), 3);
+        match self {
+            Packet::Literal(n) => {
+                push(bits, 4, 3);
+                let groups = (64 - n.leading_zeros() as usize).div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    push(bits, (i > 0) as u64, 1);
+                    push(bits, n >> (4 * i) & 0xf, 4);
+                }
+            }
+            Packet::Operator(type_id, children) => {
+                push(bits, *type_id as u64, 3);
+                let mut body = Vec::new();
+                for child in children {
+                    child.encode(rng, &mut body);
+                }
+                if body.len() < 1 << 15 && rng.chance(50) {
+                    push(bits, 0, 1);
+                    push(bits, body.len() as u64, 15);
+                } else {
+                    push(bits, 1, 1);
+                    push(bits, children.len() as u64, 11);
+                }
+                bits.extend(body);
+            }
+        }
+    }
+}
+
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let mut bits = Vec::new();
+    Packet::random(rng, size).encode(rng, &mut bits);
+    while bits.len() % 4 != 0 {
+        bits.push(false);
+    }
+    let mut out: String = bits
+        .chunks(4)
+        .map(|nibble| {
+            let n = nibble.iter().fold(0, |n, b| n << 1 | *b as u32);
+            char::from_digit(n, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect();
+    out.push('\n');
+    out
+}
+
+fn day17(rng: &mut Rng, size: usize) -> String {
+    // Stay inside the velocities the solver tries by default.
+    let distance = size.clamp(10, 150) as i64;
+    let x1 = rng.between(distance / 2, distance);
+    let x2 = x1 + rng.between(5, 40);
+    let y2 = -rng.between(distance / 4, distance / 2).max(5);
+    let y1 = y2 - rng.between(5, 40);
+    format!("target area: x={}..{}, y={}..{}\n", x1, x2, y1, y2)
+}
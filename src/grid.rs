@@ -0,0 +1,352 @@
+use crate::error::PuzzleError;
+use crate::parse::lines;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::{Index, IndexMut, RangeInclusive};
+
+/// An `(x, y)` coordinate, where `x` is the column and `y` is the row.
+pub type Point = (usize, usize);
+
+const OFFSETS4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+fn offset((x, y): Point, (dx, dy): (isize, isize)) -> Option<Point> {
+    Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+}
+
+/// A dense, rectangular grid stored in row-major order.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// The number of cells in a `width` by `height` grid, or an error if that overflows.
+fn area(width: usize, height: usize) -> Result<usize, PuzzleError> {
+    width.checked_mul(height).ok_or_else(|| {
+        PuzzleError::no_solution(format!("a {} by {} grid has too many cells", width, height))
+    })
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Result<Self, PuzzleError>
+    where
+        T: Clone,
+    {
+        Ok(Grid {
+            width,
+            height,
+            cells: vec![fill; area(width, height)?],
+        })
+    }
+
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut f: impl FnMut(Point) -> T,
+    ) -> Result<Self, PuzzleError> {
+        area(width, height)?;
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Builds a grid from its rows, or returns `None` if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        let height = rows.len();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[p.1 * self.width + p.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.cells[p.1 * self.width + p.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The orthogonal neighbours of `p` that lie inside the grid.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS4
+            .iter()
+            .filter_map(move |o| offset(p, *o))
+            .filter(move |n| self.contains(*n))
+    }
+
+    /// The orthogonal and diagonal neighbours of `p` that lie inside the grid.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS8
+            .iter()
+            .filter_map(move |o| offset(p, *o))
+            .filter(move |n| self.contains(*n))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut out = String::new();
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut f));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Grid<u8> {
+    /// Parses lines of equal length made of decimal digits.
+    pub fn parse_digits<R: BufRead>(reader: R) -> Result<Self, PuzzleError> {
+        Self::parse_digits_in(reader, 0..=9)
+    }
+
+    /// Parses lines of equal length made of decimal digits, rejecting any outside `digits`.
+    pub fn parse_digits_in<R: BufRead>(
+        reader: R,
+        digits: RangeInclusive<u8>,
+    ) -> Result<Self, PuzzleError> {
+        let expected = || match (digits.start(), digits.end()) {
+            (0, 9) => "a digit".to_string(),
+            (low, high) => format!("a digit from {} to {}", low, high),
+        };
+        let mut rows: Vec<Vec<u8>> = Vec::new();
+        for line in lines(reader) {
+            let line = line?;
+            let span = line.span();
+            if let Some(first) = rows.first() {
+                if first.len() != line.text.len() {
+                    return Err(span.error(format!("a row of {} digits", first.len())));
+                }
+            }
+            let row = span
+                .chars()
+                .map(|(c, span)| match c.to_digit(10) {
+                    Some(d) if digits.contains(&(d as u8)) => Ok(d as u8),
+                    _ => Err(span.error(expected())),
+                })
+                .collect::<Result<Vec<u8>, PuzzleError>>()?;
+            rows.push(row);
+        }
+        if rows.is_empty() || rows[0].is_empty() {
+            return Err(PuzzleError::UnexpectedEof {
+                line: 1,
+                expected: "a row of digits".to_string(),
+            });
+        }
+        Ok(Grid::from_rows(rows).expect("rows were checked to be the same length"))
+    }
+
+    pub fn to_digits(&self) -> String {
+        self.render(|d| char::from_digit(*d as u32, 10).unwrap_or('?'))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        match self.get(p) {
+            Some(cell) => cell,
+            None => panic!(
+                "point {:?} is outside of a {}x{} grid",
+                p, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(cell) => cell,
+            None => panic!("point {:?} is outside of a {}x{} grid", p, width, height),
+        }
+    }
+}
+
+/// A grid that only stores occupied cells, for large or mostly empty areas.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    /// The width and height of the smallest grid anchored at the origin holding every cell,
+    /// or an error if a cell lies on the last row or column `usize` can count to.
+    pub fn bounds(&self) -> Result<(usize, usize), PuzzleError> {
+        self.cells.keys().try_fold((0, 0), |(w, h), &(x, y)| {
+            match (x.checked_add(1), y.checked_add(1)) {
+                (Some(x), Some(y)) => Ok((usize::max(w, x), usize::max(h, y))),
+                _ => Err(PuzzleError::no_solution(format!(
+                    "the point {:?} is too far out to bound",
+                    (x, y)
+                ))),
+            }
+        })
+    }
+
+    /// The occupied orthogonal neighbours of `p`.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS4
+            .iter()
+            .filter_map(move |o| offset(p, *o))
+            .filter(move |n| self.contains(*n))
+    }
+
+    /// The occupied orthogonal and diagonal neighbours of `p`.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS8
+            .iter()
+            .filter_map(move |o| offset(p, *o))
+            .filter(move |n| self.contains(*n))
+    }
+
+    pub fn to_dense(&self, fill: T) -> Result<Grid<T>, PuzzleError>
+    where
+        T: Clone,
+    {
+        let (width, height) = self.bounds()?;
+        Grid::from_fn(width, height, |p| self.get(p).unwrap_or(&fill).clone())
+    }
+
+    pub fn render(&self, mut f: impl FnMut(Option<&T>) -> char) -> Result<String, PuzzleError> {
+        let (width, height) = self.bounds()?;
+        area(width, height)?;
+        let mut out = String::new();
+        for y in 0..height {
+            out.extend((0..width).map(|x| f(self.get((x, y)))));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter)
+    }
+}
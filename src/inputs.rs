@@ -0,0 +1,50 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The cache directory used when `AOC_INPUTS` is not set.
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// A directory of puzzle inputs keyed by day, stored as `dayN.txt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// The cache named by the `AOC_INPUTS` environment variable, or `inputs/` in the current
+    /// directory.
+    pub fn from_env() -> Self {
+        match std::env::var_os("AOC_INPUTS") {
+            Some(dir) => Cache::new(dir),
+            None => Cache::new(DEFAULT_DIR),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{}.txt", day))
+    }
+
+    pub fn contains(&self, day: u8) -> bool {
+        self.path(day).is_file()
+    }
+
+    pub fn load(&self, day: u8) -> io::Result<Vec<u8>> {
+        std::fs::read(self.path(day))
+    }
+
+    /// Saves `input` as the input for `day`, creating the cache directory if needed.
+    pub fn store(&self, day: u8, input: &[u8]) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path(day);
+        std::fs::write(&path, input)?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,46 @@
+//! Advent of Code 2021 solutions.
+//!
+//! Every `dayN` module exposes a `parse` function that reads the puzzle input into the
+//! day's input type, and `part1`/`part2` functions that solve the parsed input. Days with
+//! tunable constants also expose a `Params` struct and `part1_with`/`part2_with` functions
+//! that take it. The [`solution::SOLUTIONS`] registry wraps all of them behind the
+//! [`Solution`] trait.
+
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod generate;
+pub mod grid;
+pub mod inputs;
+pub mod memory;
+pub mod params;
+pub mod parse;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod solution;
+pub mod trace;
+pub mod watch;
+
+pub use error::{ParamError, PuzzleError};
+pub use params::Params;
+pub use solution::{Answer, Part, Solution, SOLUTIONS};
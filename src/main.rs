@@ -1,27 +1,680 @@
+use aoc2021::answers;
+use aoc2021::bench;
+use aoc2021::day2::{Model, Script};
+use aoc2021::generate;
+use aoc2021::inputs::Cache;
+use aoc2021::memory::{self, Bytes};
+use aoc2021::repl::{self, Repl};
+use aoc2021::report::{self, Run};
+use aoc2021::scaffold;
+use aoc2021::solution::{self, Part, Solution, SOLUTIONS};
+use aoc2021::trace;
+use aoc2021::watch::{History, Watcher};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::error::Error;
-use std::io::BufReader;
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn main() -> Result<(), Box<dyn Error>> {
-    let p = std::path::Path::new("day17.txt");
-    let f = std::fs::File::open(p)?;
-    println!("{}", day17::part2(BufReader::new(f)));
+use std::fs::File;
+use std::io::{BufReader, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
+
+#[derive(Parser)]
+#[command(version, about = "Advent of Code 2021 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a single day, or every day with --all
+    Run(RunArgs),
+    /// Time parsing and solving for each day and print a table sorted by median time
+    Bench(BenchArgs),
+    /// Create the module, registration and example stub for a new day
+    NewDay(NewDayArgs),
+    /// Copy a puzzle input into the input cache, or print where it is cached
+    Input(InputArgs),
+    /// Solve every day and check the answers against the recorded submissions
+    Verify(VerifyArgs),
+    /// Write a random valid input for a day
+    Generate(GenerateArgs),
+    /// Load a day's input and explore it interactively
+    Repl(ReplArgs),
+    /// Solve a day again whenever its input or example file changes
+    Watch(WatchArgs),
+    /// Export every position day 2's commands take the submarine through
+    Trajectory(TrajectoryArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to solve
+    #[arg(long, value_parser = registered_day, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Part to solve, both parts are solved when omitted
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file, or `-` for stdin; defaults to the cached `inputs/dayN.txt`
+    #[arg(long, conflicts_with = "all")]
+    input: Option<String>,
+    /// Solve every day and print a table of the answers
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+    /// Override a puzzle parameter, e.g. `--param part2_days=512`; repeatable
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_override, requires = "day")]
+    params: Vec<(String, String)>,
+    /// Print the day's parameters and their values instead of solving
+    #[arg(long, requires = "day")]
+    list_params: bool,
+    /// Solve this many days and parts at once with --all
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..), requires = "all")]
+    jobs: u16,
+    /// Read the input line by line with the day's streaming solver instead of loading it
+    #[arg(long, requires = "day", conflicts_with_all = ["params", "list_params"])]
+    stream: bool,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Also print the peak heap usage and number of allocations of each part
+    #[arg(long)]
+    memory: bool,
+    /// Print how long parsing, solving and the loops inside them took to stderr
+    #[arg(long)]
+    timings: bool,
+    /// Write the timed spans to this file in the Chrome trace format, for chrome://tracing or
+    /// Perfetto
+    #[arg(long, value_name = "FILE")]
+    trace: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Bare answers, or a table with --all
+    Text,
+    /// One JSON object per line with the answer, its type, the input hash and the elapsed time
+    Json,
+}
+
+#[derive(Args)]
+struct NewDayArgs {
+    /// Day to add
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Root of the crate to add the day to
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Day the input belongs to
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// File to copy into the cache, or `-` for stdin
+    file: Option<String>,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Only verify this day
+    #[arg(long, value_parser = registered_day)]
+    day: Option<u8>,
+    /// File of recorded answers
+    #[arg(long, default_value = answers::DEFAULT_PATH)]
+    answers: String,
+    /// Solve this many days and parts at once
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: u16,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Day to generate an input for
+    #[arg(long, value_parser = registered_day)]
+    day: u8,
+    /// How large the input is; what it counts depends on the day, and defaults to about the
+    /// size of a real puzzle input
+    #[arg(long)]
+    size: Option<usize>,
+    /// Seed for the generator; a random seed is picked and printed to stderr when omitted
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Write the input to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ReplArgs {
+    /// Day to explore
+    #[arg(long, value_parser = registered_day)]
+    day: u8,
+    /// Input file; defaults to the cached `inputs/dayN.txt`
+    #[arg(long)]
+    input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// Day to solve
+    #[arg(long, value_parser = registered_day)]
+    day: u8,
+    /// Input file; defaults to the cached `inputs/dayN.txt`
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Also watch and solve this file, such as an example pasted from the puzzle
+    #[arg(long)]
+    example: Option<PathBuf>,
+    /// Milliseconds between checks for changes
+    #[arg(long, default_value_t = 500)]
+    interval: u64,
+}
+
+#[derive(Args)]
+struct TrajectoryArgs {
+    /// Input file, or `-` for stdin; defaults to the cached `inputs/day2.txt`
+    #[arg(long)]
+    input: Option<String>,
+    /// How `down` and `up` move the submarine: `simple` as in part 1, `signed` as in part 1 but
+    /// able to go above the surface, or `aim` as in part 2
+    #[arg(long, default_value = "aim")]
+    model: Model,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Export::Csv)]
+    format: Export,
+    /// Write to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Export {
+    /// One row per position, with the command that led to it
+    Csv,
+    /// A plot of depth against horizontal position
+    Svg,
+}
+
+/// Parses a day number that has a registered solution. Days are registered by number, so they
+/// need not be consecutive.
+fn registered_day(text: &str) -> Result<u8, String> {
+    let day = text
+        .parse()
+        .map_err(|_| format!("{:?} is not a day number", text))?;
+    match solution::get(day) {
+        Some(_) => Ok(day),
+        None => Err(format!("there is no solution for day {}", day)),
+    }
+}
+
+fn parse_override(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected KEY=VALUE, found `{}`", text)),
+    }
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only benchmark this day
+    #[arg(long, value_parser = registered_day)]
+    day: Option<u8>,
+    /// Number of samples taken per stage
+    #[arg(long, default_value_t = bench::Config::default().samples)]
+    samples: usize,
+    /// Stop sampling a stage after this many seconds, keeping at least one sample
+    #[arg(long, default_value_t = bench::Config::default().max_time.as_secs_f64())]
+    max_time: f64,
+}
+
+fn read_input(path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut data = Vec::new();
+    if path == "-" {
+        std::io::stdin().lock().read_to_end(&mut data)?;
+    } else {
+        data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(data)
+}
+
+fn cached_input_path(day: u8) -> String {
+    Cache::from_env().path(day).display().to_string()
+}
+
+/// A solution paired with its cached input.
+type DayInput = (&'static dyn Solution, Vec<u8>);
+
+/// Reads the cached input of each solution, skipping days whose input has not been
+/// added yet unless `day` asks for that day by name.
+fn cached_inputs(day: Option<u8>) -> Result<Vec<DayInput>, Box<dyn Error>> {
+    let cache = Cache::from_env();
+    let mut inputs = Vec::new();
+    for &solution in SOLUTIONS {
+        if day.is_some_and(|day| day != solution.day()) {
+            continue;
+        }
+        if day.is_none() && !cache.contains(solution.day()) {
+            eprintln!(
+                "skipping day {}: no input at {}",
+                solution.day(),
+                cache.path(solution.day()).display()
+            );
+            continue;
+        }
+        inputs.push((solution, read_input(&cached_input_path(solution.day()))?));
+    }
+    Ok(inputs)
+}
+
+fn parts(part: Option<u8>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![Part::try_from(part).expect("clap restricts --part to 1 or 2")],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+fn print_row(run: &Run, memory: bool) {
+    let answer = match &run.answer {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    let usage = match (memory, run.memory) {
+        (false, _) => String::new(),
+        (true, Some(usage)) => format!(
+            "{:>10}  {:>11}  ",
+            Bytes(usage.peak_bytes),
+            usage.allocations
+        ),
+        (true, None) => format!("{:>10}  {:>11}  ", "-", "-"),
+    };
+    match answer.trim_end().split_once('\n') {
+        None => println!("{:>3}  {:>4}  {}{}", run.day, run.part, usage, answer),
+        Some(_) => println!(
+            "{}\n{}",
+            format!("{:>3}  {:>4}  {}", run.day, run.part, usage).trim_end(),
+            answer.trim_end()
+        ),
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let timings = args.timings;
+    let trace_path = args.trace.clone();
+    if timings || trace_path.is_some() {
+        trace::enable();
+    }
+    let result = solve(args);
+    let events = trace::take_events();
+    if timings {
+        eprint!("{}", trace::report(&events));
+    }
+    if let Some(path) = trace_path {
+        std::fs::write(&path, trace::chrome_trace(&events))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    result
+}
+
+fn solve(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let parts = parts(args.part);
+    if args.memory && args.jobs > 1 {
+        return Err("--memory counts allocations from every thread, so it needs --jobs 1".into());
+    }
+    if args.memory {
+        if !cfg!(feature = "count-allocations") {
+            return Err("--memory needs a build with `--features count-allocations`".into());
+        }
+        memory::enable();
+    }
+    if args.all {
+        if args.format == Format::Text && args.memory {
+            println!(
+                "{:>3}  {:>4}  {:>10}  {:>11}  answer",
+                "day", "part", "peak", "allocations"
+            );
+        } else if args.format == Format::Text {
+            println!("{:>3}  {:>4}  answer", "day", "part");
+        }
+        let inputs = cached_inputs(None)?;
+        let jobs = inputs
+            .iter()
+            .flat_map(|(solution, input)| {
+                parts.iter().map(|&part| report::Job {
+                    solution: *solution,
+                    input,
+                    part,
+                })
+            })
+            .collect::<Vec<_>>();
+        report::run_parallel(&jobs, args.jobs as usize, |run| match args.format {
+            Format::Text => print_row(&run, args.memory),
+            Format::Json => println!("{}", run.to_json()),
+        });
+        return Ok(());
+    }
+
+    let day = args.day.expect("clap requires --day without --all");
+    let solution = solution::get(day).ok_or_else(|| format!("no solution for day {}", day))?;
+    let mut params = solution.params();
+    for (key, value) in &args.params {
+        params.set(key, value)?;
+    }
+    if args.list_params {
+        for (key, value) in params.fields() {
+            println!("{}={}", key, value);
+        }
+        return Ok(());
+    }
+
+    let path = args.input.unwrap_or_else(|| cached_input_path(day));
+    let runs = if args.stream {
+        stream(day, &path, &parts)?
+    } else {
+        report::run(solution, &read_input(&path)?, &*params, &parts)
+    };
+    if args.format == Format::Json {
+        runs.iter().for_each(|run| println!("{}", run.to_json()));
+    }
+    for run in runs {
+        if let (true, Format::Text, Some(usage)) = (args.memory, args.format, run.memory) {
+            eprintln!("part {}: {}", run.part, usage);
+        }
+        let answer = run.answer.map_err(|e| format!("{}: {}", path, e))?;
+        if args.format == Format::Text {
+            println!("{}", answer);
+        }
+    }
+    Ok(())
+}
+
+fn stream(day: u8, path: &str, parts: &[Part]) -> Result<Vec<Run>, Box<dyn Error>> {
+    if path == "-" && parts.len() > 1 {
+        return Err("streaming from stdin reads the input once, so pick a --part".into());
+    }
+    let mut runs = Vec::new();
+    for &part in parts {
+        let solver = solution::streaming(day, part)
+            .ok_or_else(|| format!("day {} has no streaming solver for part {}", day, part))?;
+        let run = if path == "-" {
+            report::run_streaming(day, part, solver, std::io::stdin().lock())
+        } else {
+            let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+            report::run_streaming(day, part, solver, BufReader::new(file))
+        };
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+fn bench(args: BenchArgs) -> Result<(), Box<dyn Error>> {
+    let config = bench::Config {
+        samples: args.samples,
+        max_time: Duration::from_secs_f64(args.max_time),
+    };
+    let mut measurements = Vec::new();
+    for (solution, input) in cached_inputs(args.day)? {
+        let path = cached_input_path(solution.day());
+        measurements.extend(
+            bench::bench(solution, &input, &config).map_err(|e| format!("{}: {}", path, e))?,
+        );
+    }
+    print!("{}", bench::table(&measurements));
+    Ok(())
+}
+
+fn new_day(args: NewDayArgs) -> Result<(), Box<dyn Error>> {
+    for path in scaffold::new_day(&args.root, args.day)? {
+        println!("wrote {}", path.display());
+    }
+    println!(
+        "paste the example into tests/day{}.rs and the input into {}",
+        args.day,
+        cached_input_path(args.day)
+    );
     Ok(())
 }
+
+fn input(args: InputArgs) -> Result<(), Box<dyn Error>> {
+    let cache = Cache::from_env();
+    match args.file {
+        Some(file) => {
+            let path = cache.store(args.day, &read_input(&file)?)?;
+            println!("cached {}", path.display());
+        }
+        None if cache.contains(args.day) => println!("{}", cache.path(args.day).display()),
+        None => return Err(format!("no cached input for day {}", args.day).into()),
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), Box<dyn Error>> {
+    let answers = answers::parse(read_input(&args.answers)?.as_slice())
+        .map_err(|e| format!("{}: {}", args.answers, e))?;
+    let inputs = cached_inputs(args.day)?;
+    let jobs = inputs
+        .iter()
+        .flat_map(|(solution, input)| {
+            Part::ALL.map(|part| report::Job {
+                solution: *solution,
+                input,
+                part,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut failures = 0;
+    println!("{:>3}  {:>4}  verdict", "day", "part");
+    report::run_parallel(&jobs, args.jobs as usize, |run| {
+        let (verdict, answer) = match &run.answer {
+            Ok(answer) => {
+                let verdict = answers.check(run.day, run.part, answer);
+                if !verdict.is_ok() {
+                    failures += 1;
+                }
+                (verdict.to_string(), answer.to_string())
+            }
+            Err(e) => {
+                failures += 1;
+                (format!("error: {}", e), String::new())
+            }
+        };
+        match answer.trim_end().split_once('\n') {
+            None => println!("{:>3}  {:>4}  {}  {}", run.day, run.part, verdict, answer),
+            Some(_) => println!(
+                "{:>3}  {:>4}  {}\n{}",
+                run.day,
+                run.part,
+                verdict,
+                answer.trim_end()
+            ),
+        }
+    });
+    if failures > 0 {
+        return Err(format!("{} of {} answers failed verification", failures, jobs.len()).into());
+    }
+    Ok(())
+}
+
+fn generate(args: GenerateArgs) -> Result<(), Box<dyn Error>> {
+    let day = args.day;
+    let size = args
+        .size
+        .or_else(|| generate::default_size(day))
+        .ok_or_else(|| format!("no generator for day {}", day))?;
+    let seed = args.seed.unwrap_or_else(|| {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        eprintln!("seed: {}", nanos);
+        nanos
+    });
+    let input = generate::generate(day, size, seed)
+        .ok_or_else(|| format!("no generator for day {}", day))?;
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, input).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => print!("{}", input),
+    }
+    Ok(())
+}
+
+fn repl(args: ReplArgs) -> Result<(), Box<dyn Error>> {
+    let solution =
+        solution::get(args.day).ok_or_else(|| format!("no solution for day {}", args.day))?;
+    let path = args
+        .input
+        .unwrap_or_else(|| Cache::from_env().path(args.day));
+    let mut repl = Repl::new(solution, path)?;
+    let stdin = std::io::stdin();
+    let prompt = match stdin.is_terminal() {
+        true => format!("day{}> ", args.day),
+        false => String::new(),
+    };
+    repl::run(
+        &mut repl,
+        stdin.lock(),
+        std::io::stdout(),
+        std::io::stderr(),
+        &prompt,
+    )?;
+    Ok(())
+}
+
+fn watch(args: WatchArgs) -> Result<(), Box<dyn Error>> {
+    let solution =
+        solution::get(args.day).ok_or_else(|| format!("no solution for day {}", args.day))?;
+    let input = args
+        .input
+        .unwrap_or_else(|| Cache::from_env().path(args.day));
+    let paths: Vec<PathBuf> = std::iter::once(input).chain(args.example).collect();
+    for path in paths.iter().filter(|path| !path.is_file()) {
+        eprintln!("waiting for {} to be created", path.display());
+    }
+
+    let sources = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+    let mut watcher = Watcher::new(paths.into_iter().chain([sources.clone()]));
+    // A restart after a rebuild hands over the answers from before it.
+    let mut history = match std::env::var_os(HISTORY_VAR) {
+        Some(path) => {
+            let history = History::load(Path::new(&path)).unwrap_or_default();
+            let _ = std::fs::remove_file(&path);
+            history
+        }
+        None => History::default(),
+    };
+    let mut first = true;
+    loop {
+        for (path, input) in watcher.poll() {
+            if path == sources {
+                // The first poll only records the sources as they were built.
+                if !std::mem::take(&mut first) {
+                    rebuild(&history)?;
+                }
+                continue;
+            }
+            println!("== {}", path.display());
+            for run in report::run(solution, &input, &*solution.params(), &Part::ALL) {
+                let comparison = history.record(path, run.part, &run.answer);
+                println!("part {}: {}", run.part, comparison);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(args.interval));
+    }
+}
+
+/// The environment variable naming the file that `history` is saved to across a restart.
+const HISTORY_VAR: &str = "AOC_WATCH_HISTORY";
+
+/// Rebuilds this binary after its sources changed and, if that succeeds, replaces this process
+/// with the new build, started with the same arguments and handed `history` to compare against.
+/// A failed build keeps the old one watching; cargo has already printed why.
+fn rebuild(history: &History) -> Result<(), Box<dyn Error>> {
+    println!("== sources changed, rebuilding");
+    // Once cargo replaces the binary, the running one no longer has a path to look up.
+    let exe = std::env::current_exe()?;
+    let mut cargo = std::process::Command::new(env!("CARGO"));
+    cargo
+        .args(["build", "--quiet", "--bin", env!("CARGO_PKG_NAME")])
+        .current_dir(env!("CARGO_MANIFEST_DIR"));
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    // Build the same features as this binary, so the restart does not drop any of them.
+    let features: Vec<&str> = [
+        ("python", cfg!(feature = "python")),
+        ("count-allocations", cfg!(feature = "count-allocations")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+    if !features.is_empty() {
+        cargo.args(["--features", &features.join(",")]);
+    }
+    if !cargo.status()?.success() {
+        return Ok(());
+    }
+    let saved = std::env::temp_dir().join(format!("aoc2021-watch-{}.txt", std::process::id()));
+    history
+        .save(&saved)
+        .map_err(|e| format!("{}: {}", saved.display(), e))?;
+    let mut restart = std::process::Command::new(exe);
+    restart
+        .args(std::env::args_os().skip(1))
+        .env(HISTORY_VAR, &saved);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(restart.exec().into())
+    }
+    #[cfg(not(unix))]
+    std::process::exit(restart.status()?.code().unwrap_or(1))
+}
+
+fn trajectory(args: TrajectoryArgs) -> Result<(), Box<dyn Error>> {
+    let path = args.input.unwrap_or_else(|| cached_input_path(2));
+    let script = Script::parse(&read_input(&path)?[..]).map_err(|e| format!("{}: {}", path, e))?;
+    let trajectory = script.trajectory(args.model.start())?;
+    for step in trajectory.surfacings() {
+        eprintln!(
+            "{}: `{}` takes the submarine above the surface, to depth {}",
+            step.command.location(),
+            step.command.ins,
+            step.pos.y
+        );
+    }
+    let output = match args.format {
+        Export::Csv => trajectory.to_csv(),
+        Export::Svg => trajectory.to_svg(),
+    };
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, output).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+fn main() {
+    let result = match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Bench(args) => bench(args),
+        Command::NewDay(args) => new_day(args),
+        Command::Input(args) => input(args),
+        Command::Verify(args) => verify(args),
+        Command::Generate(args) => generate(args),
+        Command::Repl(args) => repl(args),
+        Command::Watch(args) => watch(args),
+        Command::Trajectory(args) => trajectory(args),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
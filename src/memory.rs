@@ -0,0 +1,163 @@
+//! Heap usage counting.
+//!
+//! [`Counting`] wraps the system allocator and keeps global counts of live bytes and
+//! allocations. A binary opts in by installing it and then turning counting on:
+//!
+//! ```
+//! #[global_allocator]
+//! static ALLOCATOR: aoc2021::memory::Counting = aoc2021::memory::Counting;
+//!
+//! fn main() {
+//!     aoc2021::memory::enable();
+//! }
+//! ```
+//!
+//! The `aoc2021` binary only installs it when built with the `count-allocations` feature.
+//!
+//! The counts cover every thread, including the ones legion schedules systems on, so a
+//! [`measure`]ment only describes its closure if nothing else allocates at the same time.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+// Blocks allocated before counting was enabled are subtracted when freed, so this can go below
+// zero. Only differences between readings mean anything.
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting what passes through it once [`enable`] has been called.
+pub struct Counting;
+
+impl Counting {
+    fn allocated(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            let current = CURRENT.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+            PEAK.fetch_max(current, Ordering::Relaxed);
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn freed(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            CURRENT.fetch_sub(size as isize, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Counting::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Counting::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Counting::freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Counting::freed(layout.size());
+            Counting::allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Turns counting on. Only call it with [`Counting`] installed as the global allocator, or
+/// every [`measure`]ment will be zero.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether [`enable`] has turned counting on.
+pub fn is_counting() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Heap usage over some stretch of code.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// The most bytes live at once, beyond those already live at the start.
+    pub peak_bytes: usize,
+    /// Allocations and reallocations made.
+    pub allocations: u64,
+    /// Bytes still live at the end that were not live at the start, or zero if fewer are.
+    pub retained_bytes: usize,
+}
+
+impl Usage {
+    /// The usage of running `self` and then `next`, where `self`'s retained bytes stay live.
+    pub fn then(self, next: Usage) -> Usage {
+        Usage {
+            peak_bytes: self.peak_bytes.max(self.retained_bytes + next.peak_bytes),
+            allocations: self.allocations + next.allocations,
+            retained_bytes: self.retained_bytes + next.retained_bytes,
+        }
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = format!(
+            "{} peak in {} allocations",
+            Bytes(self.peak_bytes),
+            self.allocations
+        );
+        f.pad(&text)
+    }
+}
+
+/// A byte count rendered with a binary unit, such as `1.5 MiB`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Bytes(pub usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return f.pad(&format!("{} B", self.0));
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        f.pad(&format!("{:.1} {}", value, UNITS[unit]))
+    }
+}
+
+/// Runs `f` and returns its result with the heap usage counted while it ran. The usage is all
+/// zero unless [`Counting`] is the global allocator and counting is enabled.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let outer_peak = PEAK.swap(start, Ordering::Relaxed);
+
+    let result = f();
+
+    let end = CURRENT.load(Ordering::Relaxed);
+    let peak = PEAK.fetch_max(outer_peak, Ordering::Relaxed);
+    let usage = Usage {
+        peak_bytes: (peak - start).max(0) as usize,
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        retained_bytes: (end - start).max(0) as usize,
+    };
+    (result, usage)
+}
@@ -0,0 +1,132 @@
+use crate::error::ParamError;
+use std::any::Any;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A parameter value that can be overridden from text such as `256` or `-100..=500`.
+pub trait Value: Sized {
+    const EXPECTED: &'static str;
+
+    fn parse_value(text: &str) -> Option<Self>;
+}
+
+macro_rules! integer_value {
+    ($($t:ty),*) => {
+        $(
+            impl Value for $t {
+                const EXPECTED: &'static str = "an integer";
+
+                fn parse_value(text: &str) -> Option<Self> {
+                    text.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+integer_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: Value + PartialOrd> Value for RangeInclusive<T> {
+    const EXPECTED: &'static str = "a range `A..=B` with A <= B";
+
+    fn parse_value(text: &str) -> Option<Self> {
+        let (start, end) = text.split_once("..=")?;
+        let (start, end) = (T::parse_value(start)?, T::parse_value(end)?);
+        if start > end {
+            return None;
+        }
+        Some(start..=end)
+    }
+}
+
+/// The tunable constants of a day, with the puzzle's values as defaults.
+pub trait Params: Any + Send + Sync + fmt::Debug {
+    /// Overrides the parameter named `key` with `value`.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError>;
+
+    /// Every parameter name with its current value.
+    fn fields(&self) -> Vec<(&'static str, String)>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl Params for () {
+    fn set(&mut self, key: &str, _: &str) -> Result<(), ParamError> {
+        Err(ParamError::Unknown {
+            key: key.to_string(),
+            known: Vec::new(),
+        })
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Defines a params struct whose fields have defaults and can be set by name.
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                pub $field:ident: $ty:ty = $default:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $crate::params::Params for $name {
+            fn set(&mut self, key: &str, value: &str) -> Result<(), $crate::error::ParamError> {
+                use $crate::params::Value;
+                match key {
+                    $(
+                        stringify!($field) => {
+                            self.$field = <$ty>::parse_value(value).ok_or_else(|| {
+                                $crate::error::ParamError::Invalid {
+                                    key: key.to_string(),
+                                    value: value.to_string(),
+                                    expected: <$ty>::EXPECTED,
+                                }
+                            })?;
+                            Ok(())
+                        }
+                    )*
+                    _ => Err($crate::error::ParamError::Unknown {
+                        key: key.to_string(),
+                        known: vec![$(stringify!($field)),*],
+                    }),
+                }
+            }
+
+            fn fields(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($field), format!("{:?}", self.$field))),*]
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use params;
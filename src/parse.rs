@@ -0,0 +1,142 @@
+use crate::error::PuzzleError;
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub struct Lines<R> {
+    inner: std::io::Lines<R>,
+    number: usize,
+}
+
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines {
+        inner: reader.lines(),
+        number: 0,
+    }
+}
+
+impl<R: BufRead> Lines<R> {
+    /// The number of the last line read, or 0 before the first.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn expect(&mut self, expected: &str) -> Result<Line, PuzzleError> {
+        match self.next() {
+            Some(line) => line,
+            None => Err(PuzzleError::UnexpectedEof {
+                line: self.number + 1,
+                expected: expected.to_string(),
+            }),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Line, PuzzleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = match self.inner.next()? {
+            Ok(text) => text,
+            Err(e) => return Some(Err(e.into())),
+        };
+        self.number += 1;
+        Some(Ok(Line {
+            number: self.number,
+            text,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+}
+
+impl Line {
+    pub fn span(&self) -> Span<'_> {
+        Span {
+            line: self.number,
+            column: 1,
+            text: &self.text,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Span<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    pub fn error(&self, expected: impl Into<String>) -> PuzzleError {
+        PuzzleError::Parse {
+            line: self.line,
+            column: self.column,
+            expected: expected.into(),
+            found: self.text.to_string(),
+        }
+    }
+
+    fn sub(&self, part: &'a str) -> Span<'a> {
+        let offset = part.as_ptr() as usize - self.text.as_ptr() as usize;
+        Span {
+            line: self.line,
+            column: self.column + self.text[..offset].chars().count(),
+            text: part,
+        }
+    }
+
+    pub fn trim(self) -> Span<'a> {
+        self.sub(self.text.trim())
+    }
+
+    /// Splits at byte offset `mid`, which must lie on a char boundary.
+    pub fn split_at(self, mid: usize) -> (Span<'a>, Span<'a>) {
+        let (a, b) = self.text.split_at(mid);
+        (self.sub(a), self.sub(b))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Span<'a>> + 'a {
+        self.text.split(separator).map(move |part| self.sub(part))
+    }
+
+    pub fn split_whitespace(self) -> impl Iterator<Item = Span<'a>> + 'a {
+        self.text.split_whitespace().map(move |part| self.sub(part))
+    }
+
+    pub fn split_once(
+        self,
+        separator: &str,
+        expected: &str,
+    ) -> Result<(Span<'a>, Span<'a>), PuzzleError> {
+        let (a, b) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(expected))?;
+        Ok((self.sub(a), self.sub(b)))
+    }
+
+    pub fn strip_prefix(self, prefix: &str, expected: &str) -> Result<Span<'a>, PuzzleError> {
+        match self.text.strip_prefix(prefix) {
+            Some(rest) => Ok(self.sub(rest)),
+            None => Err(self.error(expected)),
+        }
+    }
+
+    pub fn chars(self) -> impl Iterator<Item = (char, Span<'a>)> + 'a {
+        self.text
+            .char_indices()
+            .map(move |(i, c)| (c, self.sub(&self.text[i..i + c.len_utf8()])))
+    }
+
+    pub fn parse<T: FromStr>(self, expected: &str) -> Result<T, PuzzleError> {
+        self.text.parse().map_err(|_| self.error(expected))
+    }
+}
@@ -0,0 +1,306 @@
+//! Python bindings, built with the `python` feature.
+//!
+//! The `aoc2021` module solves any day from a string or a file path, and wraps a few parsed
+//! inputs as classes: day 16's `Packet`, day 13's `Manual` and day 15's `RiskGrid`. Build it
+//! with `maturin develop` or `maturin build`, which enable the `extension-module` feature and
+//! build the library as a cdylib, the same as
+//! `cargo rustc --lib --features extension-module --crate-type cdylib`.
+//!
+//! ```python
+//! import aoc2021
+//! aoc2021.solve(6, 2, "3,4,3,1,2", {"part2_days": 18})  # 26
+//! aoc2021.Packet.parse("C200B40A82").value()  # 3
+//! ```
+
+use crate::error::{ParamError, PuzzleError};
+use crate::grid::Grid;
+use crate::solution::{self, Answer, Part, Solution};
+use crate::{day13, day15, day16};
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+fn puzzle_error(e: PuzzleError) -> PyErr {
+    match e {
+        PuzzleError::Io(e) => PyOSError::new_err(e.to_string()),
+        e => PyValueError::new_err(e.to_string()),
+    }
+}
+
+fn param_error(e: ParamError) -> PyErr {
+    match e {
+        ParamError::Unknown { .. } => PyKeyError::new_err(e.to_string()),
+        ParamError::Invalid { .. } => PyValueError::new_err(e.to_string()),
+    }
+}
+
+fn read(path: &Path) -> PyResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| PyOSError::new_err(format!("{}: {}", path.display(), e)))
+}
+
+fn parse<T>(input: &[u8], parse: fn(&mut &[u8]) -> Result<T, PuzzleError>) -> PyResult<T> {
+    parse(&mut &input[..]).map_err(puzzle_error)
+}
+
+fn solve_input(
+    py: Python<'_>,
+    day: u8,
+    part: u8,
+    input: &[u8],
+    params: Option<HashMap<String, Bound<'_, PyAny>>>,
+) -> PyResult<PyObject> {
+    let solution: &dyn Solution = solution::get(day)
+        .ok_or_else(|| PyValueError::new_err(format!("no solution for day {}", day)))?;
+    let part = Part::try_from(part)
+        .map_err(|_| PyValueError::new_err(format!("part {} is not 1 or 2", part)))?;
+    let mut overrides = solution.params();
+    for (key, value) in params.unwrap_or_default() {
+        overrides
+            .set(&key, &value.str()?.to_cow()?)
+            .map_err(param_error)?;
+    }
+
+    let answer = py.allow_threads(|| {
+        let parsed = solution.parse(&mut &input[..])?;
+        solution.solve_with(part, &parsed, &*overrides)
+    });
+    Ok(match answer.map_err(puzzle_error)? {
+        Answer::Integer(n) => n.into_pyobject(py)?.into_any().unbind(),
+        Answer::Signed(n) => n.into_pyobject(py)?.into_any().unbind(),
+        Answer::Text(text) => text.into_pyobject(py)?.into_any().unbind(),
+    })
+}
+
+/// Solves one part of a day for the input `input`, returning an int or a str. `params`
+/// overrides the day's parameters, as `run --param` does.
+#[pyfunction]
+#[pyo3(signature = (day, part, input, params = None))]
+fn solve(
+    py: Python<'_>,
+    day: u8,
+    part: u8,
+    input: &str,
+    params: Option<HashMap<String, Bound<'_, PyAny>>>,
+) -> PyResult<PyObject> {
+    solve_input(py, day, part, input.as_bytes(), params)
+}
+
+/// Like `solve`, but reads the input from the file at `path`.
+#[pyfunction]
+#[pyo3(signature = (day, part, path, params = None))]
+fn solve_file(
+    py: Python<'_>,
+    day: u8,
+    part: u8,
+    path: PathBuf,
+    params: Option<HashMap<String, Bound<'_, PyAny>>>,
+) -> PyResult<PyObject> {
+    solve_input(py, day, part, &read(&path)?, params)
+}
+
+/// A day 16 packet and its sub-packets.
+#[pyclass(name = "Packet", module = "aoc2021", frozen)]
+#[derive(Clone)]
+struct PyPacket(day16::Packet);
+
+#[pymethods]
+impl PyPacket {
+    /// Decodes a hexadecimal transmission.
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        parse(text.as_bytes(), |r| day16::parse(r)).map(PyPacket)
+    }
+
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        parse(&read(&path)?, |r| day16::parse(r)).map(PyPacket)
+    }
+
+    #[getter]
+    fn version(&self) -> u8 {
+        self.0.version
+    }
+
+    #[getter]
+    fn type_id(&self) -> u8 {
+        self.0.type_id
+    }
+
+    #[getter]
+    fn operation(&self) -> &'static str {
+        self.0.operation()
+    }
+
+    /// The value of a literal packet, or None for an operator.
+    #[getter]
+    fn literal(&self) -> Option<u64> {
+        match self.0.data {
+            day16::PacketData::Literal(n) => Some(n),
+            day16::PacketData::SubPackets(_) => None,
+        }
+    }
+
+    #[getter]
+    fn children(&self) -> Vec<PyPacket> {
+        match &self.0.data {
+            day16::PacketData::Literal(_) => Vec::new(),
+            day16::PacketData::SubPackets(packets) => {
+                packets.iter().cloned().map(PyPacket).collect()
+            }
+        }
+    }
+
+    fn version_sum(&self) -> u64 {
+        self.0.version_sum()
+    }
+
+    fn value(&self) -> PyResult<u64> {
+        self.0.eval().map_err(puzzle_error)
+    }
+
+    fn tree(&self) -> String {
+        self.0.tree()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Packet(version={}, operation={:?}, children={})",
+            self.0.version,
+            self.0.operation(),
+            self.children().len()
+        )
+    }
+}
+
+/// Day 13's transparent paper: its dots and the folds to make.
+#[pyclass(name = "Manual", module = "aoc2021", frozen)]
+struct PyManual(day13::Manual);
+
+#[pymethods]
+impl PyManual {
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        parse(text.as_bytes(), |r| day13::parse(r)).map(PyManual)
+    }
+
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        parse(&read(&path)?, |r| day13::parse(r)).map(PyManual)
+    }
+
+    /// The folds as `(axis, position)` pairs, such as `("y", 7)`.
+    #[getter]
+    fn folds(&self) -> Vec<(&'static str, usize)> {
+        self.0
+            .folds
+            .iter()
+            .map(|fold| match *fold {
+                day13::Fold::X(val) => ("x", val),
+                day13::Fold::Y(val) => ("y", val),
+            })
+            .collect()
+    }
+
+    /// The `(x, y)` dots left after the first `folds` folds, or after all of them.
+    #[pyo3(signature = (folds = None))]
+    fn dots(&self, folds: Option<usize>) -> PyResult<BTreeSet<(usize, usize)>> {
+        let dots = self
+            .0
+            .folded(folds.unwrap_or(self.0.folds.len()))
+            .map_err(puzzle_error)?;
+        Ok(dots.points().collect())
+    }
+
+    /// Draws the paper after the first `folds` folds, or after all of them.
+    #[pyo3(signature = (folds = None))]
+    fn render(&self, folds: Option<usize>) -> PyResult<String> {
+        let dots = self
+            .0
+            .folded(folds.unwrap_or(self.0.folds.len()))
+            .map_err(puzzle_error)?;
+        day13::render(&dots).map_err(puzzle_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Manual(dots={}, folds={})",
+            self.0.dots.len(),
+            self.0.folds.len()
+        )
+    }
+}
+
+/// Day 15's cave of risk levels.
+#[pyclass(name = "RiskGrid", module = "aoc2021", frozen)]
+struct PyRiskGrid(Grid<u8>);
+
+#[pymethods]
+impl PyRiskGrid {
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        parse(text.as_bytes(), |r| day15::parse(r)).map(PyRiskGrid)
+    }
+
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        parse(&read(&path)?, |r| day15::parse(r)).map(PyRiskGrid)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    fn __getitem__(&self, point: (usize, usize)) -> PyResult<u8> {
+        self.0
+            .get(point)
+            .copied()
+            .ok_or_else(|| PyIndexError::new_err(format!("{:?} is outside the grid", point)))
+    }
+
+    /// The risk levels as a list of rows.
+    fn rows(&self) -> Vec<Vec<u32>> {
+        self.0
+            .rows()
+            .map(|row| row.iter().map(|&risk| risk as u32).collect())
+            .collect()
+    }
+
+    /// The cave repeated `tiles` times in each direction, as in part 2.
+    fn tiled(&self, tiles: usize) -> PyResult<PyRiskGrid> {
+        day15::tile(&self.0, tiles)
+            .map(PyRiskGrid)
+            .map_err(puzzle_error)
+    }
+
+    /// The lowest total risk of a path from the top left to the bottom right.
+    #[pyo3(signature = (tiles = 1))]
+    fn lowest_risk(&self, py: Python<'_>, tiles: usize) -> PyResult<u32> {
+        py.allow_threads(|| day15::lowest_tiled_risk(&self.0, tiles))
+            .map_err(puzzle_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RiskGrid(width={}, height={})",
+            self.0.width(),
+            self.0.height()
+        )
+    }
+}
+
+#[pymodule]
+pub fn aoc2021(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_file, m)?)?;
+    m.add_class::<PyPacket>()?;
+    m.add_class::<PyManual>()?;
+    m.add_class::<PyRiskGrid>()?;
+    Ok(())
+}
@@ -0,0 +1,433 @@
+//! An interactive prompt for exploring a day's parsed input.
+//!
+//! Every day can be solved with the current parameters, which can be changed between solves.
+//! Some days also have an explorer with their own commands, such as stepping a simulation or
+//! printing an intermediate structure.
+
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::solution::{Parsed, Part, Solution};
+use crate::{day11, day13, day16, day6};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        "solve [1|2]",
+        "solve one part, or both, with the current parameters",
+    ),
+    ("params", "list the parameters and their values"),
+    ("set KEY=VALUE", "change a parameter"),
+    ("reload", "read and parse the input file again"),
+    ("reset", "undo every step taken in the explorer"),
+    ("help", "list the commands"),
+    ("quit", "leave the prompt"),
+];
+
+/// Day-specific commands over a copy of the parsed input.
+trait Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Runs the command `name`, or returns `None` if it is not one of this explorer's.
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>>;
+}
+
+type NewExplorer = fn(&Parsed) -> Box<dyn Explorer>;
+
+static EXPLORERS: &[(u8, NewExplorer)] = &[
+    (6, |parsed| Box::new(Lanternfish::new(parsed))),
+    (11, |parsed| Box::new(Octopuses::new(parsed))),
+    (12, |parsed| Box::new(Caves::new(parsed))),
+    (13, |parsed| Box::new(Paper::new(parsed))),
+    (16, |parsed| Box::new(Transmission::new(parsed))),
+];
+
+pub struct Repl {
+    solution: &'static dyn Solution,
+    path: PathBuf,
+    parsed: Parsed,
+    params: Box<dyn Params>,
+    explorer: Option<Box<dyn Explorer>>,
+}
+
+impl Repl {
+    /// Loads and parses the input at `path` for `solution`.
+    pub fn new(solution: &'static dyn Solution, path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let parsed = load(solution, &path)?;
+        let explorer = new_explorer(solution.day(), &parsed);
+        Ok(Repl {
+            solution,
+            path,
+            parsed,
+            params: solution.params(),
+            explorer,
+        })
+    }
+
+    /// Runs one line of input and returns what it prints.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        match (name, args) {
+            ("help", []) => Ok(self.help()),
+            ("solve", _) => self.solve(args),
+            ("params", []) => {
+                let mut out = String::new();
+                for (key, value) in self.params.fields() {
+                    writeln!(out, "{}={}", key, value).unwrap();
+                }
+                Ok(out)
+            }
+            ("set", [assignment]) => {
+                let (key, value) = assignment
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected KEY=VALUE, found `{}`", assignment))?;
+                self.params.set(key, value).map_err(|e| e.to_string())?;
+                Ok(String::new())
+            }
+            ("reload", []) => {
+                self.parsed = load(self.solution, &self.path)?;
+                self.explorer = new_explorer(self.solution.day(), &self.parsed);
+                Ok(format!("reloaded {}\n", self.path.display()))
+            }
+            ("reset", []) => {
+                self.explorer = new_explorer(self.solution.day(), &self.parsed);
+                Ok(String::new())
+            }
+            _ => match self
+                .explorer
+                .as_mut()
+                .and_then(|explorer| explorer.run(name, args))
+            {
+                Some(result) => result,
+                None => Err(format!("unknown command `{}`, try `help`", line.trim())),
+            },
+        }
+    }
+
+    fn help(&self) -> String {
+        let explorer = self.explorer.as_ref().map_or(&[][..], |e| e.commands());
+        let width = COMMANDS
+            .iter()
+            .chain(explorer)
+            .map(|(usage, _)| usage.len())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::new();
+        for (usage, description) in explorer.iter().chain(COMMANDS) {
+            writeln!(out, "{:width$}  {}", usage, description, width = width).unwrap();
+        }
+        out
+    }
+
+    fn solve(&self, args: &[&str]) -> Result<String, String> {
+        let parts = match args {
+            [] => Part::ALL.to_vec(),
+            [part] => {
+                let part = part
+                    .parse::<u8>()
+                    .ok()
+                    .and_then(|n| Part::try_from(n).ok())
+                    .ok_or_else(|| format!("expected part 1 or 2, found `{}`", part))?;
+                vec![part]
+            }
+            _ => return Err("expected `solve`, `solve 1` or `solve 2`".to_string()),
+        };
+        let mut out = String::new();
+        for part in parts {
+            let answer = self
+                .solution
+                .solve_with(part, &self.parsed, &*self.params)
+                .map_err(|e| format!("part {}: {}", part, e))?;
+            let answer = answer.to_string();
+            match answer.trim_end().contains('\n') {
+                false => writeln!(out, "part {}: {}", part, answer).unwrap(),
+                true => writeln!(out, "part {}:\n{}", part, answer.trim_end()).unwrap(),
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn load(solution: &dyn Solution, path: &Path) -> Result<Parsed, String> {
+    let input = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    solution
+        .parse(&mut &input[..])
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn new_explorer(day: u8, parsed: &Parsed) -> Option<Box<dyn Explorer>> {
+    let (_, new) = EXPLORERS.iter().find(|(n, _)| *n == day)?;
+    Some(new(parsed))
+}
+
+/// Reads commands from `input` until it ends or says `quit`, writing results to `output` and
+/// errors to `errors`. Writes `prompt` before each command if it is not empty.
+pub fn run(
+    repl: &mut Repl,
+    input: impl BufRead,
+    mut output: impl Write,
+    mut errors: impl Write,
+    prompt: &str,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if !prompt.is_empty() {
+            write!(output, "{}", prompt)?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+        match repl.execute(&line) {
+            Ok(text) => write!(output, "{}", text)?,
+            Err(e) => writeln!(errors, "error: {}", e)?,
+        }
+    }
+    Ok(())
+}
+
+/// Parses the optional count argument of a stepping command.
+fn count(args: &[&str]) -> Result<usize, String> {
+    match args {
+        [] => Ok(1),
+        [n] => n
+            .parse()
+            .map_err(|_| format!("expected a number of steps, found `{}`", n)),
+        _ => Err("expected at most one number of steps".to_string()),
+    }
+}
+
+fn parsed<T: 'static>(parsed: &Parsed) -> &T {
+    parsed
+        .downcast_ref()
+        .expect("explorers are registered for their day's input type")
+}
+
+struct Lanternfish {
+    counts: [u64; 9],
+    days: usize,
+}
+
+impl Lanternfish {
+    fn new(input: &Parsed) -> Self {
+        Lanternfish {
+            counts: *parsed(input),
+            days: 0,
+        }
+    }
+
+    fn show(&self) -> String {
+        let total = self
+            .counts
+            .iter()
+            .try_fold(0u64, |total, &count| total.checked_add(count));
+        let mut out = match total {
+            Some(total) => format!("day {}: {} fish\n", self.days, total),
+            None => format!("day {}: more than {} fish\n", self.days, u64::MAX),
+        };
+        for (timer, count) in self.counts.iter().enumerate() {
+            writeln!(out, "  timer {}: {}", timer, count).unwrap();
+        }
+        out
+    }
+}
+
+impl Explorer for Lanternfish {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("show", "count the fish by timer"),
+            ("step [N]", "advance N days, 1 by default"),
+        ]
+    }
+
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>> {
+        Some(match (name, args) {
+            ("show", []) => Ok(self.show()),
+            ("step", _) => count(args).and_then(|n| {
+                for _ in 0..n {
+                    day6::step(&mut self.counts).map_err(|e| e.to_string())?;
+                    self.days += 1;
+                }
+                Ok(self.show())
+            }),
+            _ => return None,
+        })
+    }
+}
+
+struct Octopuses(day11::Cavern);
+
+impl Octopuses {
+    fn new(input: &Parsed) -> Self {
+        Octopuses(day11::Cavern::new(parsed::<Grid<u8>>(input)))
+    }
+
+    fn show(&self) -> Result<String, String> {
+        let levels = self.0.energy_levels();
+        Ok(format!(
+            "step {}: {} flashes so far\n{}",
+            self.0.steps(),
+            self.0.flashes().map_err(|e| e.to_string())?,
+            levels.render(|level| char::from_digit(*level as u32, 10).unwrap_or('*'))
+        ))
+    }
+}
+
+impl Explorer for Octopuses {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("show", "print the energy levels"),
+            (
+                "step [N]",
+                "run N steps, 1 by default, printing the flashes in each",
+            ),
+        ]
+    }
+
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>> {
+        Some(match (name, args) {
+            ("show", []) => self.show(),
+            ("step", _) => count(args).and_then(|n| {
+                let mut out = String::new();
+                for _ in 0..n {
+                    let flashes = self.0.step().map_err(|e| e.to_string())?;
+                    write!(out, "step {}: {} flashed", self.0.steps(), flashes).unwrap();
+                    if self.0.all_flashed() {
+                        out.push_str(", all of them");
+                    }
+                    out.push('\n');
+                }
+                Ok(out)
+            }),
+            _ => return None,
+        })
+    }
+}
+
+struct Caves(HashMap<String, Vec<String>>);
+
+impl Caves {
+    fn new(input: &Parsed) -> Self {
+        Caves(parsed::<HashMap<String, Vec<String>>>(input).clone())
+    }
+}
+
+impl Explorer for Caves {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("edges", "list every cave and the caves it connects to"),
+            ("neighbors CAVE", "list the caves connected to CAVE"),
+        ]
+    }
+
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let sorted = |caves: &[String]| {
+            let mut caves = caves.to_vec();
+            caves.sort();
+            caves.join(" ")
+        };
+        Some(match (name, args) {
+            ("edges", []) => {
+                let mut caves: Vec<_> = self.0.iter().collect();
+                caves.sort();
+                let mut out = String::new();
+                for (cave, neighbors) in caves {
+                    writeln!(out, "{}: {}", cave, sorted(neighbors)).unwrap();
+                }
+                Ok(out)
+            }
+            ("neighbors", [cave]) => match self.0.get(*cave) {
+                Some(neighbors) => Ok(sorted(neighbors) + "\n"),
+                None => Err(format!("there is no cave `{}`", cave)),
+            },
+            _ => return None,
+        })
+    }
+}
+
+struct Paper {
+    manual: day13::Manual,
+    folds: usize,
+}
+
+impl Paper {
+    fn new(input: &Parsed) -> Self {
+        Paper {
+            manual: parsed::<day13::Manual>(input).clone(),
+            folds: 0,
+        }
+    }
+}
+
+impl Explorer for Paper {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("show", "draw the paper after the folds made so far"),
+            ("folds", "list the folds, marking the ones made"),
+            ("fold [N]", "make the next N folds, 1 by default"),
+        ]
+    }
+
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>> {
+        Some(match (name, args) {
+            ("show", []) => self
+                .manual
+                .folded(self.folds)
+                .and_then(|dots| {
+                    let picture = day13::render(&dots)?;
+                    Ok(format!(
+                        "{} folds, {} dots\n{}",
+                        self.folds,
+                        dots.len(),
+                        picture
+                    ))
+                })
+                .map_err(|e| e.to_string()),
+            ("folds", []) => {
+                let mut out = String::new();
+                for (i, fold) in self.manual.folds.iter().enumerate() {
+                    let mark = if i < self.folds { '*' } else { ' ' };
+                    writeln!(out, "{} {}", mark, fold).unwrap();
+                }
+                Ok(out)
+            }
+            ("fold", _) => count(args).and_then(|n| {
+                let folds = self.folds.saturating_add(n).min(self.manual.folds.len());
+                let dots = self.manual.folded(folds).map_err(|e| e.to_string())?;
+                self.folds = folds;
+                Ok(format!("{} folds, {} dots\n", self.folds, dots.len()))
+            }),
+            _ => return None,
+        })
+    }
+}
+
+struct Transmission(day16::Packet);
+
+impl Transmission {
+    fn new(input: &Parsed) -> Self {
+        Transmission(parsed::<day16::Packet>(input).clone())
+    }
+}
+
+impl Explorer for Transmission {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("tree", "print the packets with their versions and values")]
+    }
+
+    fn run(&mut self, name: &str, args: &[&str]) -> Option<Result<String, String>> {
+        Some(match (name, args) {
+            ("tree", []) => Ok(self.0.tree()),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,224 @@
+use crate::error::PuzzleError;
+use crate::memory::{self, Usage};
+use crate::params::Params;
+use crate::solution::{Answer, Part, Solution, Streaming};
+use crate::trace;
+use crossbeam_channel::unbounded;
+use std::fmt::Write;
+use std::io::{self, BufRead, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The outcome of solving one part of one day.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u8,
+    pub part: Part,
+    pub input_hash: u64,
+    /// Time spent parsing the input and solving this part.
+    pub elapsed: Duration,
+    /// Heap used parsing the input and solving this part, if the counting allocator is
+    /// installed and nothing else ran at the same time.
+    pub memory: Option<Usage>,
+    pub answer: Result<Answer, PuzzleError>,
+}
+
+impl Run {
+    /// Renders the run as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write!(out, "{{\"day\":{},\"part\":{},", self.day, self.part).unwrap();
+        match &self.answer {
+            Ok(answer) => {
+                out.push_str("\"answer\":");
+                match answer {
+                    Answer::Integer(n) => write!(out, "{}", n).unwrap(),
+                    Answer::Signed(n) => write!(out, "{}", n).unwrap(),
+                    Answer::Text(text) => push_string(&mut out, text),
+                }
+                write!(out, ",\"type\":\"{}\",\"error\":null", answer.kind()).unwrap();
+            }
+            Err(e) => {
+                out.push_str("\"answer\":null,\"type\":null,\"error\":");
+                push_string(&mut out, &e.to_string());
+            }
+        }
+        write!(
+            out,
+            ",\"input_hash\":\"{:016x}\",\"elapsed_ns\":{}",
+            self.input_hash,
+            self.elapsed.as_nanos()
+        )
+        .unwrap();
+        match self.memory {
+            Some(usage) => write!(
+                out,
+                ",\"peak_bytes\":{},\"allocations\":{}}}",
+                usage.peak_bytes, usage.allocations
+            )
+            .unwrap(),
+            None => out.push_str(",\"peak_bytes\":null,\"allocations\":null}"),
+        }
+        out
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A 64-bit FNV-1a hash of the input, stable across platforms and releases.
+pub fn hash_input(input: &[u8]) -> u64 {
+    fnv1a(FNV_OFFSET, input)
+}
+
+/// Hashes everything consumed through it the same way as [`hash_input`].
+struct HashingReader<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: BufRead> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hash = fnv1a(self.hash, &buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for HashingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The bytes were just returned by fill_buf, so this does not read again.
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.hash = fnv1a(self.hash, &buf[..amt]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+fn push_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Parses `input` once and solves each of `parts` with `params`.
+pub fn run(solution: &dyn Solution, input: &[u8], params: &dyn Params, parts: &[Part]) -> Vec<Run> {
+    let _day = trace::span(format!("day {}", solution.day()));
+    let input_hash = hash_input(input);
+    let counting = memory::is_counting();
+    let start = Instant::now();
+    let (parsed, parse_usage) = memory::measure(|| {
+        let _parse = trace::span("parse");
+        solution.parse(&mut &input[..])
+    });
+    let parse_time = start.elapsed();
+    parts
+        .iter()
+        .map(|&part| {
+            let _solve = trace::span(format!("part {}", part));
+            let start = Instant::now();
+            let (answer, solve_usage) = memory::measure(|| match &parsed {
+                Ok(parsed) => solution.solve_with(part, parsed, params),
+                // Every part reports the one parse error.
+                Err(e) => Err(e.clone()),
+            });
+            Run {
+                day: solution.day(),
+                part,
+                input_hash,
+                elapsed: parse_time + start.elapsed(),
+                memory: counting.then(|| parse_usage.then(solve_usage)),
+                answer,
+            }
+        })
+        .collect()
+}
+
+/// Solves one part with a streaming solver, hashing the input as it is read.
+pub fn run_streaming(day: u8, part: Part, solver: Streaming, input: impl BufRead) -> Run {
+    let mut reader = HashingReader {
+        inner: input,
+        hash: FNV_OFFSET,
+    };
+    let _day = trace::span(format!("day {}", day));
+    let _solve = trace::span(format!("part {} streaming", part));
+    let start = Instant::now();
+    let (answer, usage) = memory::measure(|| solver(&mut reader));
+    Run {
+        day,
+        part,
+        input_hash: reader.hash,
+        elapsed: start.elapsed(),
+        memory: memory::is_counting().then_some(usage),
+        answer,
+    }
+}
+
+/// One part of one day, solved with the day's default parameters.
+#[derive(Copy, Clone)]
+pub struct Job<'a> {
+    pub solution: &'a dyn Solution,
+    pub input: &'a [u8],
+    pub part: Part,
+}
+
+/// Solves `jobs` on `threads` worker threads, handing each run to `report` in the order of
+/// `jobs` as soon as it and every run before it have finished. Runs only report their memory
+/// use when there is a single worker, since the counts are shared between threads.
+pub fn run_parallel(jobs: &[Job], threads: usize, mut report: impl FnMut(Run)) {
+    let (job_sender, job_receiver) = unbounded::<(usize, Job)>();
+    let (run_sender, run_receiver) = unbounded::<(usize, Run)>();
+    for job in jobs.iter().copied().enumerate() {
+        job_sender.send(job).unwrap();
+    }
+    drop(job_sender);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let job_receiver = job_receiver.clone();
+            let run_sender = run_sender.clone();
+            scope.spawn(move || {
+                for (i, job) in job_receiver {
+                    let params = job.solution.params();
+                    let mut run = run(job.solution, job.input, &*params, &[job.part]).remove(0);
+                    if threads > 1 {
+                        run.memory = None;
+                    }
+                    if run_sender.send((i, run)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(run_sender);
+
+        let mut pending: Vec<Option<Run>> = jobs.iter().map(|_| None).collect();
+        let mut next = 0;
+        for (i, run) in run_receiver {
+            pending[i] = Some(run);
+            while let Some(run) = pending.get_mut(next).and_then(Option::take) {
+                report(run);
+                next += 1;
+            }
+        }
+    });
+}
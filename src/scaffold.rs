@@ -0,0 +1,132 @@
+use crate::inputs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = "use crate::error::PuzzleError;
+use crate::parse::lines;
+use std::io::BufRead;
+
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<String>, PuzzleError> {
+    lines(reader).map(|line| Ok(line?.text)).collect()
+}
+
+pub fn part1(_input: &[String]) -> Result<u64, PuzzleError> {
+    Err(PuzzleError::no_solution(\"part 1 is not solved yet\"))
+}
+
+pub fn part2(_input: &[String]) -> Result<u64, PuzzleError> {
+    Err(PuzzleError::no_solution(\"part 2 is not solved yet\"))
+}
+";
+
+/// The test for a new day, ignored until the example from the puzzle is pasted in. Once the day
+/// is solved, the example can move to `tests/examples` for the regression suite instead.
+fn test_stub(day: u8) -> String {
+    format!(
+        "use aoc2021::day{day};
+
+const EXAMPLE: &str = \"\";
+
+#[test]
+#[ignore = \"day {day} is not solved yet\"]
+fn example() {{
+    let input = day{day}::parse(EXAMPLE.as_bytes()).unwrap();
+    assert_eq!(day{day}::part1(&input).unwrap(), 0);
+    assert_eq!(day{day}::part2(&input).unwrap(), 0);
+}}
+",
+        day = day
+    )
+}
+
+/// Adds day `day` to the crate at `root`: writes `src/dayN.rs` from a template, declares the
+/// module in `src/lib.rs`, registers it in `src/solution.rs`, and creates an ignored example
+/// test in `tests/dayN.rs` plus the input cache directory. Returns the files it created or
+/// changed.
+pub fn new_day(root: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("day {} is not between 1 and 25", day),
+        ));
+    }
+    let module = root.join(format!("src/day{}.rs", day));
+    if module.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", module.display()),
+        ));
+    }
+
+    let lib = root.join("src/lib.rs");
+    let lib_text = declare_module(&std::fs::read_to_string(&lib)?, day)?;
+    let solution = root.join("src/solution.rs");
+    let solution_text = register(&std::fs::read_to_string(&solution)?, day)?;
+
+    std::fs::write(&module, TEMPLATE)?;
+    std::fs::write(&lib, lib_text)?;
+    std::fs::write(&solution, solution_text)?;
+
+    let test = root.join(format!("tests/day{}.rs", day));
+    std::fs::create_dir_all(root.join("tests"))?;
+    if !test.exists() {
+        std::fs::write(&test, test_stub(day))?;
+    }
+    std::fs::create_dir_all(root.join(inputs::DEFAULT_DIR))?;
+
+    Ok(vec![module, lib, solution, test])
+}
+
+fn invalid(file: &str, reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file, reason))
+}
+
+/// Adds `pub mod dayN;` to the block of module declarations, keeping it sorted.
+fn declare_module(lib: &str, day: u8) -> io::Result<String> {
+    let lines: Vec<&str> = lib.lines().collect();
+    let first = lines
+        .iter()
+        .position(|l| l.starts_with("pub mod "))
+        .ok_or_else(|| invalid("src/lib.rs", "no module declarations"))?;
+    let count = lines[first..]
+        .iter()
+        .take_while(|l| l.starts_with("pub mod "))
+        .count();
+
+    let declaration = format!("pub mod day{};", day);
+    let mut modules: Vec<String> = lines[first..first + count]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+    if modules.contains(&declaration) {
+        return Err(invalid("src/lib.rs", "the module is already declared"));
+    }
+    modules.push(declaration);
+    modules.sort_by(|a, b| a.trim_end_matches(';').cmp(b.trim_end_matches(';')));
+
+    let mut out: Vec<String> = lines[..first].iter().map(|l| l.to_string()).collect();
+    out.extend(modules);
+    out.extend(lines[first + count..].iter().map(|l| l.to_string()));
+    Ok(out.join("\n") + "\n")
+}
+
+/// Appends `N => dayN,` to the `registry!` invocation.
+fn register(solution: &str, day: u8) -> io::Result<String> {
+    let start = solution
+        .find("registry!(\n")
+        .ok_or_else(|| invalid("src/solution.rs", "no registry! invocation"))?;
+    let end = start
+        + solution[start..]
+            .find("\n);")
+            .ok_or_else(|| invalid("src/solution.rs", "unterminated registry! invocation"))?;
+    if solution[start..end].contains(&format!("    {} => ", day)) {
+        return Err(invalid("src/solution.rs", "the day is already registered"));
+    }
+    Ok(format!(
+        "{}\n    {} => day{},{}",
+        &solution[..end],
+        day,
+        day,
+        &solution[end..]
+    ))
+}
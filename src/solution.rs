@@ -0,0 +1,253 @@
+use crate::error::PuzzleError;
+use crate::params::Params;
+use std::any::Any;
+use std::fmt;
+use std::io::BufRead;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(u64),
+    Signed(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => n.fmt(f),
+            Answer::Signed(n) => n.fmt(f),
+            Answer::Text(text) => f.pad(text),
+        }
+    }
+}
+
+impl Answer {
+    /// The variant name used in machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Signed(_) => "signed",
+            Answer::Text(_) => "text",
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(n) => Some(*n as i128),
+            Answer::Signed(n) => Some(*n as i128),
+            Answer::Text(_) => None,
+        }
+    }
+
+    /// Compares numbers by value regardless of signedness, and text exactly.
+    pub fn same_as(&self, other: &Answer) -> bool {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self == other,
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident as $repr:ty: $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $repr)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Integer as u64: u8, u16, u32, u64, usize);
+answer_from!(Signed as i64: i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(n),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+pub trait Solution: Sync {
+    fn day(&self) -> u8;
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Parsed, PuzzleError>;
+
+    /// The day's parameters set to the puzzle defaults.
+    fn params(&self) -> Box<dyn Params>;
+
+    /// Solves `parsed` with `params`, which must come from [`Solution::params`] of the same day.
+    fn solve_with(
+        &self,
+        part: Part,
+        parsed: &Parsed,
+        params: &dyn Params,
+    ) -> Result<Answer, PuzzleError>;
+
+    fn solve_parsed(&self, part: Part, parsed: &Parsed) -> Result<Answer, PuzzleError> {
+        self.solve_with(part, parsed, &*self.params())
+    }
+
+    fn part1(&self, input: &mut dyn BufRead) -> Result<Answer, PuzzleError> {
+        self.solve(Part::One, input)
+    }
+
+    fn part2(&self, input: &mut dyn BufRead) -> Result<Answer, PuzzleError> {
+        self.solve(Part::Two, input)
+    }
+
+    fn solve(&self, part: Part, input: &mut dyn BufRead) -> Result<Answer, PuzzleError> {
+        let parsed = self.parse(input)?;
+        self.solve_parsed(part, &parsed)
+    }
+}
+
+struct Day<T, P> {
+    day: u8,
+    parse: fn(&mut dyn BufRead) -> Result<T, PuzzleError>,
+    part1: fn(&T, &P) -> Result<Answer, PuzzleError>,
+    part2: fn(&T, &P) -> Result<Answer, PuzzleError>,
+}
+
+impl<T: Send + Sync + 'static, P: Params + Default> Solution for Day<T, P> {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn parse(&self, input: &mut dyn BufRead) -> Result<Parsed, PuzzleError> {
+        Ok(Box::new((self.parse)(input)?))
+    }
+
+    fn params(&self) -> Box<dyn Params> {
+        Box::new(P::default())
+    }
+
+    fn solve_with(
+        &self,
+        part: Part,
+        parsed: &Parsed,
+        params: &dyn Params,
+    ) -> Result<Answer, PuzzleError> {
+        let input = parsed
+            .downcast_ref::<T>()
+            .unwrap_or_else(|| panic!("input was not parsed by day {}", self.day));
+        let params = params
+            .as_any()
+            .downcast_ref::<P>()
+            .unwrap_or_else(|| panic!("params do not belong to day {}", self.day));
+        match part {
+            Part::One => (self.part1)(input, params),
+            Part::Two => (self.part2)(input, params),
+        }
+    }
+}
+
+macro_rules! registry {
+    ($($n:literal => $module:ident $(with $params:ident)?),* $(,)?) => {
+        pub static SOLUTIONS: &[&dyn Solution] = &[
+            $(registry!(@day $n, $module $(, $params)?),)*
+        ];
+    };
+    (@day $n:literal, $module:ident) => {
+        &Day::<_, ()> {
+            day: $n,
+            parse: |input| crate::$module::parse(input),
+            part1: |input, _| Ok(crate::$module::part1(input)?.into()),
+            part2: |input, _| Ok(crate::$module::part2(input)?.into()),
+        }
+    };
+    (@day $n:literal, $module:ident, $params:ident) => {
+        &Day::<_, crate::$module::$params> {
+            day: $n,
+            parse: |input| crate::$module::parse(input),
+            part1: |input, params| Ok(crate::$module::part1_with(input, params)?.into()),
+            part2: |input, params| Ok(crate::$module::part2_with(input, params)?.into()),
+        }
+    };
+}
+
+registry!(
+    1 => day1 with Params,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6 with Params,
+    7 => day7,
+    8 => day8,
+    9 => day9,
+    10 => day10,
+    11 => day11 with Params,
+    12 => day12,
+    13 => day13,
+    14 => day14 with Params,
+    15 => day15 with Params,
+    16 => day16,
+    17 => day17 with Params,
+);
+
+pub fn get(day: u8) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+/// A solver that reads its input line by line in bounded memory instead of parsing it up front.
+pub type Streaming = fn(&mut dyn BufRead) -> Result<Answer, PuzzleError>;
+
+macro_rules! streaming {
+    ($($n:literal => $module:ident ($part1:ident $(, $part2:ident)?)),* $(,)?) => {
+        static STREAMING: &[(u8, Part, Streaming)] = &[
+            $(
+                ($n, Part::One, |input| Ok(crate::$module::$part1(input)?.into())),
+                $(($n, Part::Two, |input| Ok(crate::$module::$part2(input)?.into())),)?
+            )*
+        ];
+    };
+}
+
+streaming!(
+    1 => day1 (part1_streaming, part2_streaming),
+    2 => day2 (part1_streaming, part2_streaming),
+    3 => day3 (part1_streaming, part2_streaming),
+    10 => day10 (part1_streaming),
+);
+
+pub fn streaming(day: u8, part: Part) -> Option<Streaming> {
+    STREAMING
+        .iter()
+        .find(|(n, p, _)| *n == day && *p == part)
+        .map(|(_, _, solver)| *solver)
+}
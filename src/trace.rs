@@ -0,0 +1,199 @@
+//! Opt-in timing spans.
+//!
+//! A [`span`] measures from its creation until it is dropped. Nothing is recorded until
+//! [`enable`] is called, so spans are cheap enough to leave in hot loops. Recorded spans are
+//! collected with [`take_events`] and rendered either as a [`report`] that sums the time spent
+//! under each path of nested spans, or as a [`chrome_trace`] file for `chrome://tracing` or
+//! Perfetto.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(Vec::new());
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+    static OPEN: RefCell<Vec<Cow<'static, str>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A finished span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The names of the spans that were open on the same thread, outermost first, ending with
+    /// this span's own name.
+    pub path: Vec<Cow<'static, str>>,
+    pub thread: u64,
+    /// When the span started, relative to when tracing was first enabled.
+    pub start: Duration,
+    pub elapsed: Duration,
+}
+
+impl Event {
+    pub fn name(&self) -> &str {
+        self.path.last().map_or("", |name| name)
+    }
+}
+
+/// Starts recording spans on every thread.
+pub fn enable() {
+    EPOCH.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Removes and returns every span recorded so far, in the order they started.
+pub fn take_events() -> Vec<Event> {
+    let mut events = std::mem::take(&mut *EVENTS.lock().unwrap());
+    events.sort_by_key(|event| (event.start, event.path.len()));
+    events
+}
+
+/// Records the time until the returned guard is dropped, if tracing is enabled.
+#[must_use = "the span ends when the guard is dropped"]
+pub fn span(name: impl Into<Cow<'static, str>>) -> Span {
+    if !is_enabled() {
+        return Span { start: None };
+    }
+    OPEN.with(|open| open.borrow_mut().push(name.into()));
+    Span {
+        start: Some(Instant::now()),
+    }
+}
+
+pub struct Span {
+    start: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        let path = OPEN.with(|open| {
+            let mut open = open.borrow_mut();
+            let path = open.clone();
+            open.pop();
+            path
+        });
+        let epoch = *EPOCH
+            .get()
+            .expect("spans only start once tracing is enabled");
+        EVENTS.lock().unwrap().push(Event {
+            path,
+            thread: THREAD.with(|thread| *thread),
+            start: start.saturating_duration_since(epoch),
+            elapsed,
+        });
+    }
+}
+
+struct Node<'a> {
+    name: &'a str,
+    calls: usize,
+    total: Duration,
+    children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn child(&mut self, name: &'a str) -> &mut Node<'a> {
+        let i = match self.children.iter().position(|child| child.name == name) {
+            Some(i) => i,
+            None => {
+                self.children.push(Node {
+                    name,
+                    calls: 0,
+                    total: Duration::ZERO,
+                    children: Vec::new(),
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[i]
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        for child in &self.children {
+            writeln!(
+                out,
+                "{:>12.3?}  {:>8}  {:>12.3?}  {:indent$}{}",
+                child.total,
+                child.calls,
+                child.total / child.calls.max(1) as u32,
+                "",
+                child.name,
+                indent = 2 * depth
+            )
+            .unwrap();
+            child.write(out, depth + 1);
+        }
+    }
+}
+
+/// Sums the time and calls of spans with the same path, across threads, and renders them as an
+/// indented tree in the order each path was first entered.
+pub fn report(events: &[Event]) -> String {
+    let mut root = Node {
+        name: "",
+        calls: 0,
+        total: Duration::ZERO,
+        children: Vec::new(),
+    };
+    let mut events: Vec<&Event> = events.iter().collect();
+    events.sort_by_key(|event| (event.start, event.path.len()));
+    for event in events {
+        let node = event
+            .path
+            .iter()
+            .fold(&mut root, |node, name| node.child(name));
+        node.calls += 1;
+        node.total += event.elapsed;
+    }
+
+    let mut out = format!("{:>12}  {:>8}  {:>12}  span\n", "total", "calls", "mean");
+    root.write(&mut out, 0);
+    out
+}
+
+/// Renders the spans as complete events in the Chrome trace event format.
+pub fn chrome_trace(events: &[Event]) -> String {
+    let mut out = String::from("{\"traceEvents\":[");
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n{\"name\":\"");
+        for c in event.name().chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+                c => out.push(c),
+            }
+        }
+        write!(
+            out,
+            "\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":1,\"tid\":{}}}",
+            event.start.as_nanos() as f64 / 1000.0,
+            event.elapsed.as_nanos() as f64 / 1000.0,
+            event.thread
+        )
+        .unwrap();
+    }
+    out.push_str("\n]}\n");
+    out
+}
@@ -0,0 +1,186 @@
+//! Polling for changed input and source files, and comparing answers between runs.
+
+use crate::error::PuzzleError;
+use crate::report::hash_input;
+use crate::solution::{Answer, Part};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Remembers a hash of each file's or directory's contents to tell when it has changed.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Watcher {
+            files: paths.into_iter().map(|path| (path, None)).collect(),
+        }
+    }
+
+    /// Returns the files whose contents changed since the last poll, with those contents, and
+    /// every readable file on the first poll. A file that cannot be read counts as unchanged
+    /// until it can be read again. The contents of a directory are the paths and contents of
+    /// every file below it.
+    pub fn poll(&mut self) -> Vec<(&Path, Vec<u8>)> {
+        let mut changed = Vec::new();
+        for (path, hash) in self.files.iter_mut() {
+            let Ok(contents) = read(path) else {
+                continue;
+            };
+            let new_hash = Some(hash_input(&contents));
+            if *hash != new_hash {
+                *hash = new_hash;
+                changed.push((path.as_path(), contents));
+            }
+        }
+        changed
+    }
+}
+
+fn read(path: &Path) -> std::io::Result<Vec<u8>> {
+    if !path.is_dir() {
+        return std::fs::read(path);
+    }
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    let mut contents = Vec::new();
+    for entry in entries {
+        contents.extend_from_slice(entry.to_string_lossy().as_bytes());
+        contents.push(0);
+        contents.extend(read(&entry)?);
+    }
+    Ok(contents)
+}
+
+/// The last outcome of each part for each watched input, kept as rendered text so that it can
+/// be saved before the watcher restarts itself and compared against after.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    outcomes: HashMap<(PathBuf, Part), String>,
+}
+
+impl History {
+    /// Records the outcome of running `part` on the input at `path`, returning it compared
+    /// against the outcome recorded before, as by [`compare`].
+    pub fn record(
+        &mut self,
+        path: &Path,
+        part: Part,
+        outcome: &Result<Answer, PuzzleError>,
+    ) -> String {
+        let current = text(outcome);
+        let key = (path.to_path_buf(), part);
+        let comparison = compare_text(self.outcomes.get(&key).map(String::as_str), &current);
+        self.outcomes.insert(key, current);
+        comparison
+    }
+
+    /// Writes every outcome, one per line as `part`, `path` and `outcome` separated by tabs,
+    /// with backslashes, tabs and newlines escaped.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = String::new();
+        for ((input, part), outcome) in &self.outcomes {
+            let input = escape(&input.to_string_lossy());
+            writeln!(out, "{}\t{}\t{}", part, input, escape(outcome)).unwrap();
+        }
+        std::fs::write(path, out)
+    }
+
+    /// Reads outcomes written by [`History::save`], skipping lines it does not understand.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let mut history = History::default();
+        for line in std::fs::read_to_string(path)?.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(part), Some(input), Some(outcome)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Some(part) = part.parse().ok().and_then(|n: u8| Part::try_from(n).ok()) else {
+                continue;
+            };
+            let key = (PathBuf::from(unescape(input)), part);
+            history.outcomes.insert(key, unescape(outcome));
+        }
+        Ok(history)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn text(outcome: &Result<Answer, PuzzleError>) -> String {
+    match outcome {
+        Ok(answer) => answer.to_string().trim_end().to_string(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+/// Renders an answer or error, marking how it differs from `previous`, the outcome of the
+/// last run of the same part. Multi-line answers start on the next line, indented, and are
+/// shown as a line diff if they changed.
+pub fn compare(
+    previous: Option<&Result<Answer, PuzzleError>>,
+    current: &Result<Answer, PuzzleError>,
+) -> String {
+    compare_text(previous.map(text).as_deref(), &text(current))
+}
+
+fn compare_text(previous: Option<&str>, current: &str) -> String {
+    let multi_line = current.contains('\n') || previous.is_some_and(|p| p.contains('\n'));
+    if !multi_line {
+        return match previous {
+            None => current.to_string(),
+            Some(previous) if previous == current => format!("{} (unchanged)", current),
+            Some(previous) => format!("{} (was {})", current, previous),
+        };
+    }
+
+    let (status, old) = match previous {
+        None => ("(new)", current),
+        Some(previous) if previous == current => ("(unchanged)", current),
+        Some(previous) => ("(changed)", previous),
+    };
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), current.lines().collect());
+    let mut out = String::from(status);
+    for i in 0..old.len().max(new.len()) {
+        match (old.get(i), new.get(i)) {
+            (Some(a), Some(b)) if a == b => write!(out, "\n  {}", b).unwrap(),
+            (a, b) => {
+                if let Some(a) = a {
+                    write!(out, "\n- {}", a).unwrap();
+                }
+                if let Some(b) = b {
+                    write!(out, "\n+ {}", b).unwrap();
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,202 @@
+//! Checks the optimized solvers against naive or brute-force versions on random inputs.
+
+use aoc2021::generate::{generate, Rng};
+use aoc2021::{day12, day14, day15, day6, day7};
+use std::collections::HashMap;
+
+const SEEDS: u64 = 40;
+
+fn input(day: u8, size: usize, seed: u64) -> String {
+    generate(day, size, seed).unwrap()
+}
+
+#[test]
+fn day6_recurrence_matches_fish_list() {
+    let mut rng = Rng::new(6);
+    for seed in 0..SEEDS {
+        let text = input(6, rng.between(1, 20) as usize, seed);
+        let counts = day6::parse(text.as_bytes()).unwrap();
+        let mut fish: Vec<u8> = text.trim().split(',').map(|n| n.parse().unwrap()).collect();
+
+        for days in 0..=80 {
+            let params = day6::Params {
+                part1_days: days,
+                part2_days: days,
+            };
+            let part1 = day6::part1_with(&counts, &params).unwrap();
+            let part2 = day6::part2_with(&counts, &params).unwrap();
+            assert_eq!(
+                part1,
+                fish.len() as u64,
+                "seed {} after {} days",
+                seed,
+                days
+            );
+            assert_eq!(part1, part2, "seed {} after {} days", seed, days);
+
+            let spawned = fish.iter().filter(|&&timer| timer == 0).count();
+            for timer in fish.iter_mut() {
+                *timer = if *timer == 0 { 6 } else { *timer - 1 };
+            }
+            fish.extend(std::iter::repeat_n(8, spawned));
+        }
+    }
+}
+
+#[test]
+fn day14_expansion_matches_pair_counting() {
+    let mut rng = Rng::new(14);
+    for seed in 0..SEEDS {
+        let text = input(14, rng.between(1, 10) as usize, seed);
+        let polymer = day14::parse(text.as_bytes()).unwrap();
+        for steps in 0..=12 {
+            let params = day14::Params {
+                part1_steps: steps,
+                part2_steps: steps,
+            };
+            let expanded = day14::part1_with(&polymer, &params).unwrap();
+            let counted = day14::part2_with(&polymer, &params).unwrap();
+            assert_eq!(
+                expanded as u64, counted,
+                "seed {} after {} steps",
+                seed, steps
+            );
+        }
+    }
+}
+
+#[test]
+fn day7_matches_brute_force() {
+    let mut rng = Rng::new(7);
+    for seed in 0..SEEDS {
+        let text = input(7, rng.between(1, 200) as usize, seed);
+        let positions = day7::parse(text.as_bytes()).unwrap();
+        // Try every position the crabs could meet at, including some outside their range.
+        let cost = |fuel: fn(i32) -> i32| {
+            (-10..2010)
+                .map(|target| {
+                    positions
+                        .iter()
+                        .map(|p| fuel((p - target).abs()))
+                        .sum::<i32>()
+                })
+                .min()
+                .unwrap()
+        };
+        assert_eq!(
+            day7::part1(&positions).unwrap(),
+            cost(|d| d) as i64,
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            day7::part2(&positions).unwrap(),
+            cost(|d| d * (d + 1) / 2) as i64,
+            "seed {}",
+            seed
+        );
+    }
+}
+
+/// Counts paths from `cave` to `end` by recursion, allowing one small cave twice if `twice`.
+fn count_paths<'a>(
+    edges: &'a HashMap<String, Vec<String>>,
+    cave: &'a str,
+    visited: &mut Vec<&'a str>,
+    twice: bool,
+) -> u32 {
+    if cave == "end" {
+        return 1;
+    }
+    let mut count = 0;
+    for next in edges[cave].iter() {
+        let small = next.chars().all(|c| c.is_ascii_lowercase());
+        let seen = small && visited.contains(&next.as_str());
+        if next == "start" || (seen && !twice) {
+            continue;
+        }
+        visited.push(next);
+        count += count_paths(edges, next, visited, twice && !seen);
+        visited.pop();
+    }
+    count
+}
+
+#[test]
+fn day12_matches_brute_force() {
+    let mut rng = Rng::new(12);
+    for seed in 0..SEEDS {
+        let text = input(12, rng.between(2, 6) as usize, seed);
+        let edges = day12::parse(text.as_bytes()).unwrap();
+        let brute = |twice| count_paths(&edges, "start", &mut vec!["start"], twice);
+        assert_eq!(day12::part1(&edges).unwrap(), brute(false), "seed {}", seed);
+        assert_eq!(day12::part2(&edges).unwrap(), brute(true), "seed {}", seed);
+    }
+}
+
+/// Relaxes every cell against its neighbours until nothing improves (Bellman-Ford).
+fn lowest_risk(risk: &[Vec<u32>]) -> u32 {
+    let (height, width) = (risk.len(), risk[0].len());
+    let mut total = vec![vec![u32::MAX; width]; height];
+    total[0][0] = 0;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for y in 0..height {
+            for x in 0..width {
+                let neighbors = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                for (nx, ny) in neighbors {
+                    if nx < width && ny < height && total[ny][nx] != u32::MAX {
+                        let candidate = total[ny][nx] + risk[y][x];
+                        if candidate < total[y][x] {
+                            total[y][x] = candidate;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    total[height - 1][width - 1]
+}
+
+#[test]
+fn day15_matches_brute_force() {
+    let mut rng = Rng::new(15);
+    for seed in 0..SEEDS {
+        let side = rng.between(1, 12) as usize;
+        let text = input(15, side, seed);
+        let grid = day15::parse(text.as_bytes()).unwrap();
+        let risk: Vec<Vec<u32>> = text
+            .lines()
+            .map(|line| line.bytes().map(|b| (b - b'0') as u32).collect())
+            .collect();
+        let tiled: Vec<Vec<u32>> = (0..side * 5)
+            .map(|y| {
+                (0..side * 5)
+                    .map(|x| {
+                        let bump = (x / side + y / side) as u32;
+                        (risk[y % side][x % side] + bump - 1) % 9 + 1
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            day15::part1(&grid).unwrap(),
+            lowest_risk(&risk),
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            day15::part2(&grid).unwrap(),
+            lowest_risk(&tiled),
+            "seed {}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use aoc2021::generate::{default_size, generate};
+use aoc2021::solution::{Part, SOLUTIONS};
+
+#[test]
+fn same_seed_same_input() {
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        if default_size(day).is_none() {
+            continue;
+        }
+        assert_eq!(generate(day, 20, 7), generate(day, 20, 7), "day {}", day);
+        assert_ne!(generate(day, 20, 7), generate(day, 20, 8), "day {}", day);
+    }
+}
+
+#[test]
+fn generated_inputs_are_solvable() {
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        // Day 17 launches the same grid of probes whatever the input, so one seed is enough.
+        let seeds = if day == 17 { 0..1 } else { 0..5 };
+        let Some(size) = default_size(day) else {
+            continue;
+        };
+        let size = size.min(50);
+        for seed in seeds {
+            let input = generate(day, size, seed).unwrap();
+            let parsed = solution
+                .parse(&mut input.as_bytes())
+                .unwrap_or_else(|e| panic!("day {} seed {}: {}\n{}", day, seed, e, input));
+            for part in Part::ALL {
+                if let Err(e) = solution.solve_parsed(part, &parsed) {
+                    panic!("day {} seed {} part {}: {}\n{}", day, seed, part, e, input);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn large_sizes_finish() {
+    // Day 12 runs out of two-letter names past 676 caves, and big day 11 grids rarely
+    // synchronize by chance.
+    for (day, size) in [(11, 300), (12, 700)] {
+        let solution = aoc2021::solution::get(day).unwrap();
+        let input = generate(day, size, 1).unwrap();
+        let parsed = solution.parse(&mut input.as_bytes()).unwrap();
+        if day == 11 {
+            assert!(solution.solve_parsed(Part::Two, &parsed).is_ok());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use aoc2021::grid::{Grid, SparseGrid};
+
+#[test]
+fn digits_round_trip() {
+    let text = "2199943210\n3987894921\n9856789892\n";
+    let grid = Grid::parse_digits(text.as_bytes()).unwrap();
+    assert_eq!((grid.width(), grid.height()), (10, 3));
+    assert_eq!(grid[(1, 0)], 1);
+    assert_eq!(grid.to_digits(), text);
+}
+
+#[test]
+fn ragged_digits_are_rejected() {
+    let err = Grid::parse_digits("123\n12\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 1: expected a row of 3 digits, found \"12\""
+    );
+}
+
+#[test]
+fn neighbors_stay_in_bounds() {
+    let grid = Grid::new(3, 2, 0u8).unwrap();
+    assert_eq!(
+        grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+        [(1, 0), (0, 1)]
+    );
+    assert_eq!(grid.neighbors8((0, 0)).count(), 3);
+    assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+    assert_eq!(grid.get((3, 0)), None);
+}
+
+#[test]
+fn oversized_grids_are_rejected() {
+    assert!(Grid::new(usize::MAX, 2, 0u8).is_err());
+    assert!(Grid::from_fn(2, usize::MAX, |_| 0u8).is_err());
+}
+
+#[test]
+fn sparse_renders_from_origin() {
+    let grid: SparseGrid<bool> = [((0, 0), true), ((2, 1), true)].into_iter().collect();
+    assert_eq!(grid.bounds().unwrap(), (3, 2));
+    assert_eq!(
+        grid.render(|c| if c.is_some() { '#' } else { '.' })
+            .unwrap(),
+        "#..\n..#\n"
+    );
+    assert_eq!(
+        grid.to_dense(false)
+            .unwrap()
+            .render(|c| if *c { '#' } else { '.' }),
+        "#..\n..#\n"
+    );
+}
+
+#[test]
+fn sparse_bounds_do_not_overflow() {
+    let grid: SparseGrid<()> = [((usize::MAX, 0), ())].into_iter().collect();
+    assert!(grid.bounds().is_err());
+    assert!(grid.render(|_| '#').is_err());
+}
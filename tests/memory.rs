@@ -0,0 +1,66 @@
+use aoc2021::memory::{self, Bytes, Counting, Usage};
+use aoc2021::report;
+use aoc2021::solution;
+use aoc2021::Part;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+// The counts are shared by every thread, including the other tests in this file, so the
+// checks only rely on lower bounds.
+
+#[test]
+fn measures_peak_and_retained_bytes() {
+    memory::enable();
+    assert!(memory::is_counting());
+    let (kept, usage) = memory::measure(|| {
+        let scratch = vec![1u8; 1 << 20];
+        drop(std::hint::black_box(scratch));
+        vec![2u8; 1 << 10]
+    });
+    assert!(usage.peak_bytes >= 1 << 20, "{:?}", usage);
+    assert!(usage.allocations >= 2, "{:?}", usage);
+    assert!(usage.retained_bytes >= 1 << 10, "{:?}", usage);
+    drop(kept);
+}
+
+#[test]
+fn runs_report_memory() {
+    memory::enable();
+    let input = std::fs::read("tests/examples/day5.txt").unwrap();
+    let solution = solution::get(5).unwrap();
+    let runs = report::run(solution, &input, &*solution.params(), &Part::ALL);
+    for run in runs {
+        let usage = run.memory.unwrap();
+        assert!(usage.peak_bytes > 0 && usage.allocations > 0, "{:?}", usage);
+    }
+}
+
+#[test]
+fn usage_adds_up_in_sequence() {
+    let parse = Usage {
+        peak_bytes: 100,
+        allocations: 2,
+        retained_bytes: 40,
+    };
+    let solve = Usage {
+        peak_bytes: 80,
+        allocations: 5,
+        retained_bytes: 0,
+    };
+    assert_eq!(
+        parse.then(solve),
+        Usage {
+            peak_bytes: 120,
+            allocations: 7,
+            retained_bytes: 40,
+        }
+    );
+}
+
+#[test]
+fn bytes_use_binary_units() {
+    assert_eq!(Bytes(1023).to_string(), "1023 B");
+    assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+    assert_eq!(Bytes(5 << 30).to_string(), "5.0 GiB");
+}
@@ -0,0 +1,297 @@
+use aoc2021::day2::{self, Ins, Model, NavigationModel, Pos, Pos2, Script, SignedPos};
+use aoc2021::PuzzleError;
+
+const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+fn script(text: &str) -> Script {
+    Script::parse(text.as_bytes()).unwrap()
+}
+
+fn parse_error(text: &str) -> (usize, usize, String) {
+    match Script::parse(text.as_bytes()) {
+        Err(PuzzleError::Parse {
+            line,
+            column,
+            expected,
+            ..
+        }) => (line, column, expected),
+        other => panic!("{:?} parsed as {:?}", text, other),
+    }
+}
+
+#[test]
+fn puzzle_inputs_are_scripts() {
+    let example = script(EXAMPLE);
+    let instructions = day2::parse(EXAMPLE.as_bytes()).unwrap();
+    let ins: Vec<Ins> = example.commands().map(|command| command.ins).collect();
+    assert_eq!(ins, instructions);
+    assert_eq!(example.solve(Model::Simple).unwrap(), 150);
+    assert_eq!(example.solve(Model::Aim).unwrap(), 900);
+}
+
+#[test]
+fn repeats_macros_and_comments() {
+    let text = "\
+# Dive, then creep along the bottom.
+macro dive {
+    down 2  # steeper each time
+    forward 1
+}
+repeat 3 {
+    dive
+    repeat 2 {
+        forward 1
+    }
+}
+back 4
+repeat 0 {
+    down 100
+}
+";
+    let script = script(text);
+    let lines: Vec<usize> = script.commands().map(|command| command.line).collect();
+    assert_eq!(lines, [3, 4, 9, 9, 3, 4, 9, 9, 3, 4, 9, 9, 12]);
+    assert_eq!(script.run(Pos::default()).unwrap(), Pos { x: 5, y: 6 });
+    assert_eq!(
+        script.run(Pos2::default()).unwrap(),
+        Pos2 {
+            x: 5,
+            y: 2 * 3 + 4 * 3 + 6 * 3 - 6 * 4,
+            aim: 6
+        }
+    );
+}
+
+#[test]
+fn errors_point_at_the_command() {
+    assert_eq!(
+        parse_error("forward 1\nsideways 2\n"),
+        (
+            2,
+            1,
+            "an instruction, `repeat N {`, `macro NAME {`, `}` or a defined macro".to_string()
+        )
+    );
+    assert_eq!(parse_error("down x\n"), (1, 6, "a distance".to_string()));
+    assert_eq!(
+        parse_error("repeat 2 {\n  dive\n}\n"),
+        (
+            2,
+            3,
+            "an instruction, `repeat N {`, `macro NAME {`, `}` or a defined macro".to_string()
+        )
+    );
+    assert_eq!(
+        parse_error("macro up {\n}\n"),
+        (1, 7, "a new macro name".to_string())
+    );
+    assert_eq!(parse_error("repeat 2 [\n}\n"), (1, 10, "`{`".to_string()));
+    assert_eq!(parse_error("}\n").2, "a command outside any block");
+    assert!(matches!(
+        Script::parse("repeat 2 {\nforward 1\n".as_bytes()),
+        Err(PuzzleError::UnexpectedEof { line: 3, .. })
+    ));
+
+    let rises = script("down 2\nrepeat 2 {\n  up 1 # fine twice\n}\nup 1\n");
+    assert_eq!(
+        rises.run(Pos::default()).unwrap_err().to_string(),
+        "no solution: line 5: `up 1` would rise above the surface"
+    );
+    assert_eq!(rises.run(Pos2::default()).unwrap().aim, -1);
+    assert_eq!(
+        script("back 1\n")
+            .run(Pos::default())
+            .unwrap_err()
+            .to_string(),
+        "no solution: line 1: `back 1` would back up past the start"
+    );
+    assert!(script("down 70000\nforward 70000\n")
+        .run(Pos2::default())
+        .is_err());
+}
+
+#[test]
+fn macro_errors_point_at_the_call() {
+    let text = "\
+macro rise {
+    up 1
+}
+macro bob {
+    down 1
+    rise
+    rise
+}
+bob
+down 1
+bob
+";
+    let script = script(text);
+    let calls: Vec<Option<usize>> = script.commands().map(|command| command.called).collect();
+    assert_eq!(
+        calls,
+        [
+            Some(9),
+            Some(9),
+            Some(9),
+            None,
+            Some(11),
+            Some(11),
+            Some(11)
+        ]
+    );
+    assert_eq!(
+        script.run(Pos::default()).unwrap_err().to_string(),
+        "no solution: line 9 (macro line 2): `up 1` would rise above the surface"
+    );
+
+    let trajectory = script.trajectory(SignedPos::default()).unwrap();
+    let csv = trajectory.to_csv();
+    assert_eq!(
+        csv.lines().skip(1).take(4).collect::<Vec<_>>(),
+        [
+            "0,,,,0,0,0",
+            "1,5,9,down 1,0,1,0",
+            "2,2,9,up 1,0,0,0",
+            "3,2,9,up 1,0,-1,0"
+        ]
+    );
+    let surfacing = trajectory.surfacings().next().unwrap();
+    assert_eq!(surfacing.command.location(), "line 9 (macro line 2)");
+}
+
+#[test]
+fn trajectories_follow_every_command() {
+    let instructions = day2::parse(EXAMPLE.as_bytes()).unwrap();
+    let aim = day2::trajectory(&instructions, Pos2::default()).unwrap();
+    let depths: Vec<i32> = aim.states().map(|pos| pos.y).collect();
+    assert_eq!(depths, [0, 0, 0, 40, 40, 40, 60]);
+    assert_eq!(
+        aim.end(),
+        Pos2 {
+            x: 15,
+            y: 60,
+            aim: 10
+        }
+    );
+    assert_eq!(aim.surfacings().count(), 0);
+
+    let simple = day2::trajectory(&instructions, SignedPos::default()).unwrap();
+    assert_eq!(
+        simple.to_csv().lines().take(3).collect::<Vec<_>>(),
+        [
+            "step,line,called,command,x,depth,aim",
+            "0,,,,0,0,0",
+            "1,1,,forward 5,5,0,0"
+        ]
+    );
+    assert_eq!(
+        simple.to_csv().lines().last(),
+        Some("6,6,,forward 2,15,10,0")
+    );
+    assert!(!simple.to_svg().contains("<circle"));
+}
+
+#[test]
+fn part1_fails_when_the_submarine_surfaces() {
+    // Rising above the surface used to wrap the unsigned depth around.
+    let text = "down 2\nforward 1\nup 3\n";
+    let instructions = day2::parse(text.as_bytes()).unwrap();
+    let message = "no solution: line 3: `up 3` would rise above the surface";
+    assert_eq!(day2::part1(&instructions).unwrap_err().to_string(), message);
+    assert_eq!(
+        day2::part1_streaming(text.as_bytes())
+            .unwrap_err()
+            .to_string(),
+        message
+    );
+    assert_eq!(day2::part2(&instructions).unwrap(), 2);
+}
+
+#[test]
+fn oversized_answers_are_errors() {
+    let message = "no solution: the horizontal position times the depth is too large";
+    let deep = day2::parse("forward 70000\ndown 70000\n".as_bytes()).unwrap();
+    assert_eq!(day2::part1(&deep).unwrap_err().to_string(), message);
+    let text = "down 1\nforward 50000\nforward 50000\n";
+    let far = day2::parse(text.as_bytes()).unwrap();
+    assert_eq!(day2::part2(&far).unwrap_err().to_string(), message);
+    assert_eq!(
+        day2::part2_streaming(text.as_bytes())
+            .unwrap_err()
+            .to_string(),
+        message
+    );
+}
+
+#[test]
+fn negative_depths_are_reported() {
+    let text = "down 2\nforward 1\nup 3\ndown 5\nup 7\n";
+    let instructions = day2::parse(text.as_bytes()).unwrap();
+    assert_eq!(
+        day2::trajectory(&instructions, Pos::default())
+            .unwrap_err()
+            .to_string(),
+        "no solution: line 3: `up 3` would rise above the surface"
+    );
+    let trajectory = day2::trajectory(&instructions, SignedPos::default()).unwrap();
+    assert_eq!(trajectory.model, "signed");
+    let surfaced: Vec<(usize, i32)> = trajectory
+        .surfacings()
+        .map(|step| (step.command.line, step.pos.y))
+        .collect();
+    assert_eq!(surfaced, [(3, -1), (5, -3)]);
+    let svg = trajectory.to_svg();
+    assert_eq!(svg.matches("<circle").count(), 2);
+    assert!(svg.contains("<title>line 5: up 7</title>"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn models_compare_in_one_pass() {
+    let models: Vec<Box<dyn NavigationModel>> = vec![
+        Box::new(Pos::default()),
+        Box::new(Pos2::default()),
+        Model::Simple.start(),
+    ];
+    let outcomes = day2::compare(models, day2::commands(EXAMPLE.as_bytes())).unwrap();
+    let ends: Vec<_> = outcomes
+        .iter()
+        .map(|outcome| (outcome.model.name(), outcome.model.position()))
+        .collect();
+    assert_eq!(
+        ends,
+        [
+            ("simple", (15, 10)),
+            ("aim", (15, 60)),
+            ("simple", (15, 10))
+        ]
+    );
+    assert!(outcomes.iter().all(|outcome| outcome.error.is_none()));
+
+    let models: Vec<Box<dyn NavigationModel>> = vec![Model::Simple.start(), Model::Aim.start()];
+    let text = "forward 2\nup 1\nforward 3\n";
+    let outcomes = day2::compare(models, day2::commands(text.as_bytes())).unwrap();
+    assert_eq!(outcomes[0].model.position(), (2, 0));
+    assert_eq!(
+        outcomes[0].error.as_ref().unwrap().to_string(),
+        "no solution: line 2: `up 1` would rise above the surface"
+    );
+    assert_eq!(outcomes[1].model.position(), (5, -3));
+    assert!(outcomes[1].error.is_none());
+
+    let models: Vec<Box<dyn NavigationModel>> = vec![Model::Aim.start()];
+    assert!(day2::compare(models, day2::commands("forward 2\nleft 1\n".as_bytes())).is_err());
+}
+
+#[test]
+fn one_parser_feeds_every_model() {
+    let simple = day2::navigate(Pos::default(), day2::commands(EXAMPLE.as_bytes())).unwrap();
+    let aim = day2::navigate(Pos2::default(), day2::commands(EXAMPLE.as_bytes())).unwrap();
+    assert_eq!(simple, Pos { x: 15, y: 10 });
+    assert_eq!(aim.position(), (15, 60));
+
+    let boxed = day2::navigate(Model::Aim.start(), day2::commands(EXAMPLE.as_bytes())).unwrap();
+    assert_eq!(boxed.name(), "aim");
+    assert_eq!(boxed.position(), aim.position());
+    assert_eq!(script(EXAMPLE).run(Pos2::default()).unwrap(), aim);
+}
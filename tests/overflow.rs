@@ -0,0 +1,95 @@
+use aoc2021::{day12, day13, day16, day5, day7};
+
+#[test]
+fn day16_overflowing_values_fail() {
+    let product = day16::parse("0401E848C210842108400246108421084200".as_bytes()).unwrap();
+    assert_eq!(
+        day16::part2(&product).unwrap_err().to_string(),
+        "no solution: a packet's value does not fit in 64 bits"
+    );
+    let sum = day16::parse("0001844FFFFFFFFFFFFFFFFFFEF102".as_bytes()).unwrap();
+    assert!(day16::part2(&sum).is_err());
+    assert_eq!(day16::part1(&sum).unwrap(), 0);
+}
+
+#[test]
+fn day16_deep_nesting_fails() {
+    // Sums of one sub-packet each, nested `depth` deep around a literal zero.
+    let transmission = |depth: usize| {
+        let mut bits = "000000100000000001".repeat(depth);
+        bits.push_str("00010000000000");
+        bits.extend(std::iter::repeat_n('0', (4 - bits.len() % 4) % 4));
+        bits.as_bytes()
+            .chunks(4)
+            .map(|nibble| {
+                let n = u8::from_str_radix(std::str::from_utf8(nibble).unwrap(), 2).unwrap();
+                char::from_digit(n as u32, 16).unwrap()
+            })
+            .collect::<String>()
+    };
+    let packet = day16::parse(transmission(day16::MAX_DEPTH).as_bytes()).unwrap();
+    assert_eq!(day16::part2(&packet).unwrap(), 0);
+
+    let err = day16::parse(transmission(1000).as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("line 1, column 1157: expected packets nested at most 256 deep"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn day7_far_apart_crabs_fit() {
+    let positions = day7::parse("0,100000".as_bytes()).unwrap();
+    assert_eq!(day7::part1(&positions).unwrap(), 100000);
+    assert_eq!(day7::part2(&positions).unwrap(), 2 * (50000 * 50001 / 2));
+    assert!(day7::part1(&[]).is_err());
+    assert!(day7::part2(&[]).is_err());
+}
+
+#[test]
+fn day5_many_overlapping_vents_fit() {
+    let vents = day5::parse("0,0 -> 0,0\n".repeat(300).as_bytes()).unwrap();
+    assert_eq!(day5::part1(&vents).unwrap(), 1);
+    assert_eq!(day5::part2(&vents).unwrap(), 1);
+}
+
+#[test]
+fn day5_far_vents_are_rejected() {
+    let err =
+        day5::parse("0,0 -> 0,0\n5000000000,5000000000 -> 5000000000,5000000000\n".as_bytes())
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 1: expected a coordinate no greater than 4095, found \"5000000000\""
+    );
+    let far = day5::Vent {
+        x1: usize::MAX - 1,
+        y1: usize::MAX - 1,
+        x2: usize::MAX - 1,
+        y2: usize::MAX - 1,
+    };
+    assert!(day5::part2(&[far]).is_err());
+}
+
+#[test]
+fn day13_far_dots_are_not_drawn() {
+    let manual = day13::parse("18446744073709551615,0\n\nfold along y=0\n".as_bytes()).unwrap();
+    assert!(day13::part2(&manual).is_err());
+
+    let manual = day13::parse("5000000,0\n\nfold along y=0\n".as_bytes()).unwrap();
+    assert_eq!(
+        day13::part2(&manual).unwrap_err().to_string(),
+        "no solution: the dots span 5000001 by 1 cells, too many to draw"
+    );
+}
+
+#[test]
+fn day12_linked_big_caves_are_rejected() {
+    let err = day12::parse("start-A\nA-B\nB-end\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 1: expected an edge with at least one small cave, found \"A-B\""
+    );
+}
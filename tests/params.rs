@@ -0,0 +1,75 @@
+use aoc2021::solution::{self, Part};
+use aoc2021::{day14, day15, day6, ParamError, Params};
+
+#[test]
+fn library_overrides() {
+    let counts = day6::parse("3,4,3,1,2".as_bytes()).unwrap();
+    let params = day6::Params {
+        part1_days: 18,
+        ..Default::default()
+    };
+    assert_eq!(day6::part1_with(&counts, &params).unwrap(), 26);
+    assert_eq!(day6::part1(&counts).unwrap(), 5934);
+}
+
+#[test]
+fn registry_overrides() {
+    let day6 = solution::get(6).unwrap();
+    let parsed = day6.parse(&mut "3,4,3,1,2".as_bytes()).unwrap();
+    let mut params = day6.params();
+    params.set("part2_days", "18").unwrap();
+    let answer = day6.solve_with(Part::Two, &parsed, &*params).unwrap();
+    assert_eq!(answer.to_string(), "26");
+}
+
+#[test]
+fn rejected_overrides() {
+    let mut params = aoc2021::day17::Params::default();
+    assert!(matches!(
+        params.set("part1_x", "10..=5"),
+        Err(ParamError::Invalid { .. })
+    ));
+    assert!(matches!(
+        params.set("speed", "1"),
+        Err(ParamError::Unknown { .. })
+    ));
+    params.set("part1_y", "-5..=5").unwrap();
+    assert_eq!(params.part1_y, -5..=5);
+}
+
+#[test]
+fn overflowing_overrides_fail() {
+    let counts = day6::parse("3,4,3,1,2".as_bytes()).unwrap();
+    let params = day6::Params {
+        part2_days: 600,
+        ..Default::default()
+    };
+    assert!(day6::part2_with(&counts, &params).is_err());
+
+    let input = std::fs::read("tests/examples/day14.txt").unwrap();
+    let polymer = day14::parse(&input[..]).unwrap();
+    let params = day14::Params {
+        part2_steps: 200,
+        ..Default::default()
+    };
+    assert!(day14::part2_with(&polymer, &params).is_err());
+    assert_eq!(day14::part2(&polymer).unwrap(), 2188189693529);
+
+    let cave = day15::parse("19\n21\n".as_bytes()).unwrap();
+    for tiles in [100000, 1 << 62] {
+        let params = day15::Params {
+            part2_tiles: tiles,
+            ..Default::default()
+        };
+        assert!(day15::part2_with(&cave, &params).is_err());
+    }
+}
+
+#[test]
+fn zero_risks_are_rejected() {
+    let err = day15::parse("12\n10\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 2: expected a digit from 1 to 9, found \"0\""
+    );
+}
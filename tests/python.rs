@@ -0,0 +1,46 @@
+#![cfg(feature = "python")]
+
+use aoc2021::python::aoc2021;
+use pyo3::ffi::c_str;
+use pyo3::prelude::*;
+
+#[test]
+fn bindings_return_python_values() {
+    pyo3::append_to_inittab!(aoc2021);
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        py.run(
+            c_str!(
+                r#"
+import aoc2021
+
+assert aoc2021.solve(6, 1, "3,4,3,1,2") == 5934
+assert aoc2021.solve(6, 2, "3,4,3,1,2", {"part2_days": 18}) == 26
+assert aoc2021.solve_file(13, 1, "tests/examples/day13.txt") == 17
+try:
+    aoc2021.solve(6, 1, "3,4,3,1,2", {"days": 3})
+    raise AssertionError("unknown parameter accepted")
+except KeyError:
+    pass
+
+packet = aoc2021.Packet.parse("C200B40A82")
+assert (packet.operation, packet.value(), packet.version_sum()) == ("sum", 3, 14)
+assert [child.literal for child in packet.children] == [1, 2]
+
+manual = aoc2021.Manual.from_file("tests/examples/day13.txt")
+assert manual.folds == [("y", 7), ("x", 5)]
+assert len(manual.dots(1)) == 17
+assert manual.render().splitlines()[0] == "█████"
+
+grid = aoc2021.RiskGrid.from_file("tests/examples/day15.txt")
+assert grid[0, 0] == 1 and grid.rows()[0][:3] == [1, 1, 6]
+assert (grid.lowest_risk(), grid.lowest_risk(5)) == (40, 315)
+assert grid.tiled(5).width == 50
+"#
+            ),
+            None,
+            None,
+        )
+    })
+    .unwrap();
+}
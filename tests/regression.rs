@@ -0,0 +1,165 @@
+//! Runs every day and part against the puzzle inputs in `inputs/` and the published examples in
+//! `tests/examples`, comparing the answers with `tests/answers.txt`.
+//!
+//! After an intentional change in answers, regenerate the data file with
+//! `cargo test --test regression -- --bless`.
+
+use aoc2021::inputs;
+use aoc2021::solution::{Part, SOLUTIONS};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const ANSWERS: &str = "tests/answers.txt";
+const EXAMPLES: &str = "tests/examples";
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn inputs() -> Vec<(PathBuf, u8)> {
+    let mut inputs = Vec::new();
+    for solution in SOLUTIONS {
+        let path = Path::new(inputs::DEFAULT_DIR).join(format!("day{}.txt", solution.day()));
+        if root().join(&path).exists() {
+            inputs.push((path, solution.day()));
+        }
+    }
+
+    let mut examples = std::fs::read_dir(root().join(EXAMPLES))
+        .expect("examples directory")
+        .map(|entry| entry.expect("examples directory entry").file_name())
+        .filter_map(|name| name.into_string().ok())
+        .collect::<Vec<_>>();
+    examples.sort();
+    for name in examples {
+        let day = name
+            .strip_prefix("day")
+            .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|day| day.parse().ok());
+        match day {
+            Some(day) => inputs.push((Path::new(EXAMPLES).join(name), day)),
+            None => panic!("example {} is not named after its day", name),
+        }
+    }
+
+    inputs
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut out = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn solve(path: &Path, day: u8, part: Part) -> String {
+    let input = std::fs::read(root().join(path)).expect("readable input");
+    let solution = SOLUTIONS
+        .iter()
+        .find(|s| s.day() == day)
+        .expect("registered day");
+    match solution.solve(part, &mut input.as_slice()) {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+fn load_answers() -> BTreeMap<(String, Part), String> {
+    let text = std::fs::read_to_string(root().join(ANSWERS)).unwrap_or_default();
+    let mut answers = BTreeMap::new();
+    for line in text
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    {
+        let fields: Vec<&str> = line.splitn(3, '\t').collect();
+        match fields.as_slice() {
+            [path, part, answer] => {
+                let part = match *part {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => panic!("invalid part in {}: {:?}", ANSWERS, line),
+                };
+                answers.insert((path.to_string(), part), unescape(answer));
+            }
+            _ => panic!("invalid line in {}: {:?}", ANSWERS, line),
+        }
+    }
+    answers
+}
+
+fn bless() {
+    let mut out = String::from("# input\tpart\tanswer\n");
+    for (path, day) in inputs() {
+        for part in Part::ALL {
+            let answer = solve(&path, day, part);
+            writeln!(out, "{}\t{}\t{}", path.display(), part, escape(&answer)).unwrap();
+        }
+    }
+    std::fs::write(root().join(ANSWERS), out).expect("writable answers file");
+    println!("blessed {}", ANSWERS);
+}
+
+fn main() {
+    if std::env::args().any(|arg| arg == "--bless") {
+        return bless();
+    }
+
+    let answers = load_answers();
+    let mut failures = Vec::new();
+    let mut passed = 0;
+    for (path, day) in inputs() {
+        for part in Part::ALL {
+            let name = format!("{} part {}", path.display(), part);
+            let actual = solve(&path, day, part);
+            match answers.get(&(path.display().to_string(), part)) {
+                Some(expected) if *expected == actual => {
+                    println!("test {} ... ok", name);
+                    passed += 1;
+                }
+                Some(expected) => {
+                    println!("test {} ... FAILED", name);
+                    failures.push(format!(
+                        "{}: expected {:?}, got {:?}",
+                        name, expected, actual
+                    ));
+                }
+                None => {
+                    println!("test {} ... FAILED", name);
+                    failures.push(format!("{}: no recorded answer, got {:?}", name, actual));
+                }
+            }
+        }
+    }
+
+    println!();
+    for failure in failures.iter() {
+        println!("{}", failure);
+    }
+    println!(
+        "test result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        passed,
+        failures.len()
+    );
+    if !failures.is_empty() {
+        println!("run `cargo test --test regression -- --bless` to accept the new answers");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,72 @@
+use aoc2021::repl::{self, Repl};
+use aoc2021::solution;
+
+fn session(day: u8, example: &str, commands: &str) -> (String, String) {
+    let solution = solution::get(day).unwrap();
+    let mut repl = Repl::new(solution, format!("tests/examples/{}", example)).unwrap();
+    let (mut output, mut errors) = (Vec::new(), Vec::new());
+    repl::run(&mut repl, commands.as_bytes(), &mut output, &mut errors, "").unwrap();
+    (
+        String::from_utf8(output).unwrap(),
+        String::from_utf8(errors).unwrap(),
+    )
+}
+
+#[test]
+fn solves_with_changed_params() {
+    let (output, errors) = session(
+        6,
+        "day6.txt",
+        "solve\nset part1_days=18\nsolve 1\nset days=1\nsolve 3\nquit\nsolve\n",
+    );
+    assert_eq!(output, "part 1: 5934\npart 2: 26984457539\npart 1: 26\n");
+    assert_eq!(
+        errors,
+        "error: unknown parameter `days`: expected one of part1_days, part2_days\n\
+         error: expected part 1 or 2, found `3`\n"
+    );
+}
+
+#[test]
+fn steps_and_resets_simulations() {
+    let (output, errors) = session(11, "day11.txt", "step 2\nreset\nstep\nstep 1 2\n");
+    assert_eq!(
+        output,
+        "step 1: 0 flashed\nstep 2: 35 flashed\nstep 1: 0 flashed\n"
+    );
+    assert_eq!(errors, "error: expected at most one number of steps\n");
+}
+
+#[test]
+fn folds_paper_one_fold_at_a_time() {
+    let (output, errors) = session(13, "day13.txt", "fold\nfolds\nfold 9\nshow\ntree\n");
+    assert_eq!(
+        output,
+        "1 folds, 17 dots\n\
+         * fold along y=7\n  fold along x=5\n\
+         2 folds, 16 dots\n\
+         2 folds, 16 dots\n█████\n█   █\n█   █\n█   █\n█████\n"
+    );
+    assert_eq!(errors, "error: unknown command `tree`, try `help`\n");
+}
+
+#[test]
+fn prints_packet_tree() {
+    let (output, _) = session(16, "day16-a.txt", "tree\n");
+    assert_eq!(
+        output,
+        "sum v5 = 54\n  sum v1 = 54\n    sum v3 = 54\n      literal v7 = 6\n      literal v6 = 6\n\
+         \x20     literal v5 = 12\n      literal v2 = 15\n      literal v2 = 15\n"
+    );
+}
+
+#[test]
+fn large_counts_do_not_overflow() {
+    let (output, errors) = session(6, "day6.txt", "step 490\n");
+    assert!(output.starts_with("day 490: more than 18446744073709551615 fish\n"));
+    assert_eq!(errors, "");
+
+    let (output, errors) = session(13, "day13.txt", "fold\nfold 18446744073709551615\n");
+    assert_eq!(output, "1 folds, 17 dots\n2 folds, 16 dots\n");
+    assert_eq!(errors, "");
+}
@@ -0,0 +1,95 @@
+use aoc2021::memory::Usage;
+use aoc2021::report::{self, hash_input, run_parallel, Job, Run};
+use aoc2021::solution;
+use aoc2021::{Answer, Part, PuzzleError};
+use std::time::Duration;
+
+#[test]
+fn fnv1a_reference_values() {
+    assert_eq!(hash_input(b""), 0xcbf29ce484222325);
+    assert_eq!(hash_input(b"a"), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+fn json_escapes_text_answers() {
+    let run = Run {
+        day: 13,
+        part: Part::Two,
+        input_hash: 0xff,
+        elapsed: Duration::from_micros(5),
+        memory: Some(Usage {
+            peak_bytes: 2048,
+            allocations: 3,
+            retained_bytes: 0,
+        }),
+        answer: Ok(Answer::Text("█ \"\n".to_string())),
+    };
+    assert_eq!(
+        run.to_json(),
+        r#"{"day":13,"part":2,"answer":"█ \"\n","type":"text","error":null,"input_hash":"00000000000000ff","elapsed_ns":5000,"peak_bytes":2048,"allocations":3}"#
+    );
+}
+
+#[test]
+fn json_reports_errors() {
+    let run = Run {
+        day: 1,
+        part: Part::One,
+        input_hash: 0,
+        elapsed: Duration::ZERO,
+        memory: None,
+        answer: Err(PuzzleError::no_solution("empty")),
+    };
+    assert_eq!(
+        run.to_json(),
+        r#"{"day":1,"part":1,"answer":null,"type":null,"error":"no solution: empty","input_hash":"0000000000000000","elapsed_ns":0,"peak_bytes":null,"allocations":null}"#
+    );
+}
+
+#[test]
+fn parse_errors_are_reported_for_every_part() {
+    let solution = solution::get(1).unwrap();
+    let runs = report::run(solution, b"1\nx\n", &*solution.params(), &Part::ALL);
+    let errors: Vec<String> = runs
+        .iter()
+        .map(|run| run.answer.as_ref().unwrap_err().to_string())
+        .collect();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0], errors[1]);
+    assert!(errors[0].starts_with("line 2, column 1:"), "{}", errors[0]);
+}
+
+#[test]
+fn parallel_runs_keep_job_order() {
+    let inputs: Vec<_> = [1, 6, 7, 14]
+        .into_iter()
+        .map(|day| {
+            let path = format!(
+                "{}/tests/examples/day{}.txt",
+                env!("CARGO_MANIFEST_DIR"),
+                day
+            );
+            (solution::get(day).unwrap(), std::fs::read(path).unwrap())
+        })
+        .collect();
+    let jobs: Vec<_> = inputs
+        .iter()
+        .flat_map(|(solution, input)| {
+            Part::ALL.map(|part| Job {
+                solution: *solution,
+                input,
+                part,
+            })
+        })
+        .collect();
+
+    let mut runs = Vec::new();
+    run_parallel(&jobs, 3, |run| runs.push(run));
+    let order: Vec<_> = runs.iter().map(|run| (run.day, run.part)).collect();
+    let expected: Vec<_> = jobs
+        .iter()
+        .map(|job| (job.solution.day(), job.part))
+        .collect();
+    assert_eq!(order, expected);
+    assert_eq!(runs[2].answer.as_ref().unwrap().to_string(), "5934");
+}
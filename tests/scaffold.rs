@@ -0,0 +1,43 @@
+use aoc2021::scaffold::new_day;
+use std::fs;
+use std::path::PathBuf;
+
+fn scratch_crate(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "//! Docs.\n\npub mod bench;\npub mod day1;\npub mod day2;\npub mod error;\n\npub use error::PuzzleError;\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/solution.rs"),
+        "registry!(\n    1 => day1,\n    2 => day2,\n);\n",
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn registers_a_new_day() {
+    let root = scratch_crate("new-day");
+    new_day(&root, 12).unwrap();
+
+    let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+    assert!(lib.contains("pub mod day1;\npub mod day12;\npub mod day2;\n"));
+    let solution = fs::read_to_string(root.join("src/solution.rs")).unwrap();
+    assert_eq!(
+        solution,
+        "registry!(\n    1 => day1,\n    2 => day2,\n    12 => day12,\n);\n"
+    );
+    assert!(root.join("src/day12.rs").exists());
+    let test = fs::read_to_string(root.join("tests/day12.rs")).unwrap();
+    assert!(test.contains("#[test]\n#[ignore = \"day 12 is not solved yet\"]\nfn example()"));
+    assert!(!root.join("tests/examples/day12.txt").exists());
+    assert!(root.join("inputs").is_dir());
+
+    assert!(new_day(&root, 12).is_err());
+    assert!(new_day(&root, 26).is_err());
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,124 @@
+use aoc2021::day1::{
+    anomalies, longest_increasing_run, max_drop, moving_average, parse, part2, part2_streaming,
+    window_increases, Anomaly,
+};
+use aoc2021::generate::Rng;
+use aoc2021::PuzzleError;
+
+const EXAMPLE: [u64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+/// The sums of every window of `n` depths, added up from scratch.
+fn window_sums(depths: &[u64], n: usize) -> Vec<u64> {
+    if n > depths.len() {
+        return Vec::new();
+    }
+    (0..=depths.len() - n)
+        .map(|i| depths[i..i + n].iter().sum())
+        .collect()
+}
+
+#[test]
+fn window_increases_match_window_sums() {
+    assert_eq!(window_increases(&EXAMPLE, 1).unwrap(), 7);
+    assert_eq!(window_increases(&EXAMPLE, 3).unwrap(), 5);
+    assert!(window_increases(&EXAMPLE, 0).is_err());
+
+    let mut rng = Rng::new(1);
+    for _ in 0..50 {
+        let depths: Vec<u64> = (0..rng.below(40)).map(|_| rng.below(300)).collect();
+        for n in 1..12 {
+            let sums = window_sums(&depths, n);
+            let naive = sums.windows(2).filter(|w| w[1] > w[0]).count();
+            assert_eq!(
+                window_increases(&depths, n).unwrap(),
+                naive,
+                "{:?} n={}",
+                depths,
+                n
+            );
+        }
+    }
+}
+
+#[test]
+fn window_statistics() {
+    let averages = moving_average(&EXAMPLE, 3);
+    assert_eq!(averages.len(), 8);
+    assert!((averages[0] - 607.0 / 3.0).abs() < 1e-9);
+    assert!((averages[7] - 792.0 / 3.0).abs() < 1e-9);
+    assert!(moving_average(&EXAMPLE, 0).is_empty());
+    assert!(moving_average(&EXAMPLE, 11).is_empty());
+
+    assert_eq!(max_drop(&EXAMPLE, 1).unwrap(), Some(10));
+    assert_eq!(max_drop(&EXAMPLE, 3).unwrap(), Some(1));
+    assert_eq!(max_drop(&[1, 2, 3], 1).unwrap(), None);
+
+    assert_eq!(longest_increasing_run(&EXAMPLE, 1).unwrap(), 3);
+    assert_eq!(longest_increasing_run(&EXAMPLE, 3).unwrap(), 4);
+    assert_eq!(longest_increasing_run(&[], 2).unwrap(), 0);
+    assert!(max_drop(&EXAMPLE, 0).is_err());
+    assert!(longest_increasing_run(&EXAMPLE, 0).is_err());
+}
+
+#[test]
+fn depths_beyond_u16() {
+    let input = "70000\n70000\n70000\n4000000000000\n";
+    let depths = parse(input.as_bytes()).unwrap();
+    assert_eq!(depths[3], 4_000_000_000_000);
+    assert_eq!(part2(&depths).unwrap(), 1);
+    assert_eq!(part2_streaming(input.as_bytes()).unwrap(), 1);
+    assert_eq!(max_drop(&[u64::MAX, 0], 1).unwrap(), Some(u64::MAX));
+    assert_eq!(moving_average(&[u64::MAX, u64::MAX], 2), [u64::MAX as f64]);
+}
+
+#[test]
+fn rejects_lines_that_are_not_depths() {
+    let expected = |input: &str| match parse(input.as_bytes()) {
+        Err(PuzzleError::Parse { line, expected, .. }) => (line, expected),
+        other => panic!("{:?} parsed as {:?}", input, other),
+    };
+    assert_eq!(
+        expected("1\n-5\n"),
+        (2, "a depth that is not negative".to_string())
+    );
+    assert_eq!(expected("1\n2\n3x\n"), (3, "a depth".to_string()));
+    assert_eq!(expected("+4\n"), (1, "a depth".to_string()));
+    assert_eq!(expected("\n"), (1, "a depth".to_string()));
+    assert_eq!(
+        expected("1\n99999999999999999999\n"),
+        (2, format!("a depth of at most {}", u64::MAX))
+    );
+}
+
+#[test]
+fn reports_gaps_and_jumps() {
+    let input = "100\n105\n\n104\n900\n880\n\n\n100\n";
+    assert_eq!(
+        anomalies(input.as_bytes(), 50).unwrap(),
+        [
+            Anomaly::Gap { line: 3 },
+            Anomaly::Jump {
+                line: 5,
+                from: 104,
+                to: 900
+            },
+            Anomaly::Gap { line: 7 },
+            Anomaly::Gap { line: 8 },
+            Anomaly::Jump {
+                line: 9,
+                from: 880,
+                to: 100
+            },
+        ]
+    );
+    assert_eq!(
+        Anomaly::Gap { line: 3 }.to_string(),
+        "line 3: missing depth"
+    );
+    assert!(
+        anomalies(EXAMPLE.map(|d| d.to_string()).join("\n").as_bytes(), 33)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(anomalies("1\n-2\n".as_bytes(), 10).is_err());
+}
@@ -0,0 +1,121 @@
+use aoc2021::solution::{self, Part};
+use std::path::Path;
+
+fn check_day(day: u8) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let inputs = [
+        root.join(format!("inputs/day{}.txt", day)),
+        root.join(format!("tests/examples/day{}.txt", day)),
+    ];
+    let solution = solution::get(day).unwrap();
+    for path in inputs.iter().filter(|path| path.exists()) {
+        let input = std::fs::read(path).unwrap();
+        for part in Part::ALL {
+            let Some(streaming) = solution::streaming(day, part) else {
+                continue;
+            };
+            assert_eq!(
+                streaming(&mut input.as_slice()).unwrap(),
+                solution.solve(part, &mut input.as_slice()).unwrap(),
+                "{} part {}",
+                path.display(),
+                part
+            );
+        }
+    }
+}
+
+#[test]
+fn day1_matches() {
+    check_day(1);
+}
+
+#[test]
+fn day2_matches() {
+    check_day(2);
+}
+
+#[test]
+fn day3_matches() {
+    check_day(3);
+}
+
+#[test]
+fn day10_matches() {
+    check_day(10);
+}
+
+#[test]
+fn day10_part2_does_not_stream() {
+    assert!(solution::streaming(10, Part::Two).is_none());
+}
+
+#[test]
+fn day10_scores_do_not_overflow() {
+    let corrupted = "(>\n".repeat(200_000);
+    let lines = aoc2021::day10::parse(corrupted.as_bytes()).unwrap();
+    assert_eq!(aoc2021::day10::part1(&lines).unwrap(), 200_000 * 25137);
+    assert_eq!(
+        aoc2021::day10::part1_streaming(corrupted.as_bytes()).unwrap(),
+        200_000 * 25137
+    );
+
+    let deep = format!("{}\n", "(".repeat(30));
+    let lines = aoc2021::day10::parse(deep.as_bytes()).unwrap();
+    assert_eq!(
+        aoc2021::day10::part2(&lines).unwrap_err().to_string(),
+        "no solution: a completion score is too large to count"
+    );
+}
+
+#[test]
+fn day1_deep_windows_do_not_overflow() {
+    let input = "30000\n30000\n30000\n30001\n";
+    let depths = aoc2021::day1::parse(input.as_bytes()).unwrap();
+    assert_eq!(aoc2021::day1::part2(&depths).unwrap(), 1);
+    assert_eq!(aoc2021::day1::part2_streaming(input.as_bytes()).unwrap(), 1);
+}
+
+#[test]
+fn day3_duplicates_and_errors() {
+    let input = "101\n101\n010\n";
+    let rows = aoc2021::day3::parse(input.as_bytes()).unwrap();
+    assert_eq!(
+        aoc2021::day3::part2(&rows).unwrap(),
+        aoc2021::day3::part2_streaming(input.as_bytes()).unwrap()
+    );
+    assert!(aoc2021::day3::part2_streaming("".as_bytes()).is_err());
+    assert!(aoc2021::day3::part1_streaming("10\n102\n".as_bytes()).is_err());
+}
+
+#[test]
+fn day3_co2_keeps_candidates_sharing_a_bit() {
+    let input = "100\n101\n000\n001\n011\n";
+    let rows = aoc2021::day3::parse(input.as_bytes()).unwrap();
+    assert_eq!(aoc2021::day3::part2(&rows).unwrap(), 4);
+    assert_eq!(aoc2021::day3::part2_streaming(input.as_bytes()).unwrap(), 4);
+}
+
+#[test]
+fn day3_wide_ratings_do_not_overflow() {
+    let input = "10101010101010101010\n";
+    let rows = aoc2021::day3::parse(input.as_bytes()).unwrap();
+    let (gamma, epsilon) = (0b10101010101010101010u64, 0b01010101010101010101);
+    assert_eq!(aoc2021::day3::part1(&rows).unwrap(), gamma * epsilon);
+    assert_eq!(
+        aoc2021::day3::part1_streaming(input.as_bytes()).unwrap(),
+        gamma * epsilon
+    );
+    assert_eq!(aoc2021::day3::part2(&rows).unwrap(), gamma * gamma);
+    assert_eq!(
+        aoc2021::day3::part2_streaming(input.as_bytes()).unwrap(),
+        gamma * gamma
+    );
+
+    let too_wide = format!("{}\n", "1".repeat(33));
+    let rows = aoc2021::day3::parse(too_wide.as_bytes()).unwrap();
+    assert_eq!(
+        aoc2021::day3::part1(&rows).unwrap_err().to_string(),
+        "no solution: numbers of 33 bits are too wide"
+    );
+}
@@ -0,0 +1,78 @@
+use aoc2021::report;
+use aoc2021::solution;
+use aoc2021::trace::{self, Event};
+use aoc2021::Part;
+use std::borrow::Cow;
+use std::time::Duration;
+
+fn event(path: &[&'static str], start_us: u64, elapsed_us: u64) -> Event {
+    Event {
+        path: path.iter().map(|name| Cow::Borrowed(*name)).collect(),
+        thread: 1,
+        start: Duration::from_micros(start_us),
+        elapsed: Duration::from_micros(elapsed_us),
+    }
+}
+
+#[test]
+fn report_sums_spans_by_path() {
+    let events = [
+        event(&["day 1"], 0, 100),
+        event(&["day 1", "parse"], 0, 10),
+        event(&["day 1", "part 1"], 10, 90),
+        event(&["day 1", "part 1", "step"], 10, 30),
+        event(&["day 1", "part 1", "step"], 40, 50),
+    ];
+    let report = trace::report(&events);
+    let rows: Vec<Vec<&str>> = report
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            vec!["total", "calls", "mean", "span"],
+            vec!["100.000µs", "1", "100.000µs", "day", "1"],
+            vec!["10.000µs", "1", "10.000µs", "parse"],
+            vec!["90.000µs", "1", "90.000µs", "part", "1"],
+            vec!["80.000µs", "2", "40.000µs", "step"],
+        ]
+    );
+    assert!(report.contains("\n    80.000µs         2      40.000µs      step\n"));
+}
+
+#[test]
+fn chrome_trace_has_complete_events() {
+    let trace = trace::chrome_trace(&[event(&["day 1", "say \"hi\""], 1, 2500)]);
+    assert_eq!(
+        trace,
+        "{\"traceEvents\":[\n{\"name\":\"say \\\"hi\\\"\",\"ph\":\"X\",\"ts\":1.000,\"dur\":2500.000,\"pid\":1,\"tid\":1}\n]}\n"
+    );
+}
+
+#[test]
+fn runs_record_nested_spans() {
+    let input = std::fs::read("tests/examples/day11.txt").unwrap();
+    let solution = solution::get(11).unwrap();
+    trace::enable();
+    report::run(solution, &input, &*solution.params(), &[Part::One]);
+    trace::disable();
+
+    let events = trace::take_events();
+    let paths: Vec<Vec<&str>> = events
+        .iter()
+        .map(|event| event.path.iter().map(|name| &**name).collect())
+        .collect();
+    assert_eq!(paths[0], ["day 11"]);
+    assert_eq!(paths[1], ["day 11", "parse"]);
+    assert_eq!(paths[2], ["day 11", "part 1"]);
+    let steps = paths
+        .iter()
+        .filter(|path| **path == ["day 11", "part 1", "step"])
+        .count();
+    assert_eq!(steps, 100);
+    assert!(paths.contains(&vec!["day 11", "part 1", "step", "flash cascade"]));
+    assert!(events
+        .iter()
+        .all(|event| event.elapsed <= events[0].elapsed));
+}
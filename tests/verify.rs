@@ -0,0 +1,72 @@
+use aoc2021::answers::{self, Verdict};
+use aoc2021::{Answer, Part};
+
+const HISTORY: &str = r##"
+# submissions
+[day1.part1]
+wrong = [10, "ten"]  # typo
+too_high = [500, 200]
+too_low = 20
+
+[day1.part2]
+correct = -7
+
+[day13.part2]
+correct = "#.\n.#"
+"##;
+
+#[test]
+fn verdicts_follow_submit_history() {
+    let answers = answers::parse(HISTORY.as_bytes()).unwrap();
+    let check = |part, answer: Answer| answers.check(1, part, &answer);
+
+    assert_eq!(check(Part::One, 10u32.into()), Verdict::KnownWrong);
+    assert_eq!(
+        check(Part::One, 300u32.into()),
+        Verdict::TooHigh {
+            bound: Answer::Integer(200)
+        }
+    );
+    assert_eq!(
+        check(Part::One, 5u32.into()),
+        Verdict::TooLow {
+            bound: Answer::Integer(20)
+        }
+    );
+    assert_eq!(check(Part::One, 100u32.into()), Verdict::Unknown);
+    assert_eq!(check(Part::Two, (-7i32).into()), Verdict::Correct);
+    assert!(!check(Part::Two, 7u32.into()).is_ok());
+    assert_eq!(
+        answers.check(13, Part::Two, &"#.\n.#".to_string().into()),
+        Verdict::Correct
+    );
+    assert_eq!(answers.check(2, Part::One, &1u8.into()), Verdict::Unknown);
+}
+
+#[test]
+fn numbers_match_across_signedness() {
+    let answers = answers::parse("[day2.part2]\ncorrect = 1965970888\n".as_bytes()).unwrap();
+    let signed = Answer::Signed(1965970888);
+    assert_eq!(answers.check(2, Part::Two, &signed), Verdict::Correct);
+}
+
+#[test]
+fn malformed_files_report_positions() {
+    let err = answers::parse("correct = 1\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 1, column 1: expected a `[dayN.partM]` table before any keys, found \"correct = 1\""
+    );
+    let err = answers::parse("[day1.part1]\nwrong = [1, 2\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 14: expected `,` or `]`, found \"\""
+    );
+}
+
+#[test]
+fn bundled_answers_parse() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/", "answers.toml");
+    let answers = answers::parse(std::fs::read(path).unwrap().as_slice()).unwrap();
+    assert_eq!(answers.iter().count(), 34);
+}
@@ -0,0 +1,104 @@
+use aoc2021::watch::{compare, History, Watcher};
+use aoc2021::{Answer, Part, PuzzleError};
+use std::path::Path;
+
+#[test]
+fn polls_report_changed_contents() {
+    let dir = std::env::temp_dir().join(format!("aoc2021-watch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+    std::fs::write(&a, "1\n").unwrap();
+    let _ = std::fs::remove_file(&b);
+
+    let mut watcher = Watcher::new([a.clone(), b.clone()]);
+    assert_eq!(watcher.poll(), [(a.as_path(), b"1\n".to_vec())]);
+    assert!(watcher.poll().is_empty());
+
+    std::fs::write(&a, "1\n").unwrap();
+    std::fs::write(&b, "2\n").unwrap();
+    assert_eq!(watcher.poll(), [(b.as_path(), b"2\n".to_vec())]);
+
+    std::fs::write(&a, "3\n").unwrap();
+    std::fs::remove_file(&b).unwrap();
+    assert_eq!(watcher.poll(), [(a.as_path(), b"3\n".to_vec())]);
+    assert!(watcher.poll().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn polls_report_changes_below_a_directory() {
+    let dir = std::env::temp_dir().join(format!("aoc2021-watch-dir-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("nested/a.rs"), "1").unwrap();
+
+    let mut watcher = Watcher::new([dir.clone()]);
+    assert_eq!(watcher.poll().len(), 1);
+    assert!(watcher.poll().is_empty());
+
+    std::fs::write(dir.join("nested/a.rs"), "2").unwrap();
+    assert_eq!(watcher.poll().len(), 1);
+    std::fs::write(dir.join("b.rs"), "").unwrap();
+    assert_eq!(watcher.poll().len(), 1);
+    assert!(watcher.poll().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compares_single_line_answers() {
+    let ok = |n: u64| Ok(Answer::Integer(n));
+    assert_eq!(compare(None, &ok(5)), "5");
+    assert_eq!(compare(Some(&ok(5)), &ok(5)), "5 (unchanged)");
+    assert_eq!(compare(Some(&ok(4)), &ok(5)), "5 (was 4)");
+    assert_eq!(
+        compare(Some(&ok(4)), &Err(PuzzleError::no_solution("empty"))),
+        "error: no solution: empty (was 4)"
+    );
+}
+
+#[test]
+fn diffs_multi_line_answers() {
+    let text = |s: &str| Ok(Answer::Text(s.to_string()));
+    assert_eq!(compare(None, &text("ab\ncd\n")), "(new)\n  ab\n  cd");
+    assert_eq!(
+        compare(Some(&text("ab\ncd\n")), &text("ab\ncd\n")),
+        "(unchanged)\n  ab\n  cd"
+    );
+    assert_eq!(
+        compare(Some(&text("ab\ncd\n")), &text("ab\nce\nef\n")),
+        "(changed)\n  ab\n- cd\n+ ce\n+ ef"
+    );
+}
+
+#[test]
+fn history_carries_answers_across_a_restart() {
+    let saved = std::env::temp_dir().join(format!("aoc2021-history-{}.txt", std::process::id()));
+    let input = Path::new("inputs/day\t13.txt");
+    let text = |s: &str| Ok(Answer::Text(s.to_string()));
+
+    let mut before = History::default();
+    assert_eq!(
+        before.record(input, Part::One, &Ok(Answer::Integer(4))),
+        "4"
+    );
+    assert_eq!(
+        before.record(input, Part::Two, &text("a\\b\ncd\n")),
+        "(new)\n  a\\b\n  cd"
+    );
+    before.save(&saved).unwrap();
+
+    // The sources changed, so the watcher rebuilt itself and starts over from the saved file.
+    let mut after = History::load(&saved).unwrap();
+    assert_eq!(after, before);
+    assert_eq!(
+        after.record(input, Part::One, &Ok(Answer::Integer(5))),
+        "5 (was 4)"
+    );
+    assert_eq!(
+        after.record(input, Part::Two, &text("a\\b\nce\n")),
+        "(changed)\n  a\\b\n- cd\n+ ce"
+    );
+
+    std::fs::remove_file(&saved).unwrap();
+}